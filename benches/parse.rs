@@ -0,0 +1,45 @@
+#[macro_use]
+extern crate criterion;
+extern crate bitcoin_amount;
+
+use std::hint::black_box;
+use std::str::FromStr;
+
+use bitcoin_amount::{Amount, SignedAmount};
+use criterion::Criterion;
+
+/// Amounts as they show up in block explorer and RPC output.
+const INPUTS: [&str; 6] = ["0.00000546", "0.1", "1.23456789", "50", "21000000", "-0.00012345"];
+
+fn from_str(c: &mut Criterion) {
+    c.bench_function("Amount::from_str", |b| {
+        b.iter(|| {
+            for s in INPUTS[..5].iter() {
+                black_box(Amount::from_str(black_box(s)).unwrap());
+            }
+        })
+    });
+    c.bench_function("SignedAmount::from_str", |b| {
+        b.iter(|| {
+            for s in INPUTS.iter() {
+                black_box(SignedAmount::from_str(black_box(s)).unwrap());
+            }
+        })
+    });
+    c.bench_function("f64::from_str", |b| {
+        b.iter(|| {
+            for s in INPUTS.iter() {
+                black_box(f64::from_str(black_box(s)).unwrap());
+            }
+        })
+    });
+}
+
+fn from_sat_str(c: &mut Criterion) {
+    c.bench_function("Amount::from_sat_str", |b| {
+        b.iter(|| black_box(Amount::from_sat_str(black_box("2100000000000000")).unwrap()))
+    });
+}
+
+criterion_group!(benches, from_str, from_sat_str);
+criterion_main!(benches);
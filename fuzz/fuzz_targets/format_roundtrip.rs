@@ -0,0 +1,21 @@
+//! Formats arbitrary amounts in every denomination, the result must parse
+//! back to the exact same amount.
+
+#![no_main]
+
+use bitcoin_amount::{Amount, Denomination, SignedAmount};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|sat: i64| {
+    let amt = SignedAmount::from_sat(sat);
+    for &denom in Denomination::ALL.iter() {
+        let formatted = amt.display_in(denom).to_string();
+        assert_eq!(SignedAmount::from_str_in(&formatted, denom), Ok(amt));
+
+        let trimmed = amt.display_in(denom).trim_trailing_zeros(true).to_string();
+        assert_eq!(SignedAmount::from_str_in(&trimmed, denom), Ok(amt));
+    }
+
+    let amt = Amount::from_sat(sat as u64);
+    assert_eq!(amt.to_string().parse::<Amount>(), Ok(amt));
+});
@@ -0,0 +1,17 @@
+//! Parses arbitrary strings as BTC amounts, whatever parses must format back
+//! to an equal amount.
+
+#![no_main]
+
+use bitcoin_amount::{Amount, SignedAmount};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|s: &str| {
+    if let Ok(amt) = s.parse::<Amount>() {
+        assert_eq!(amt.to_string().parse::<Amount>(), Ok(amt));
+    }
+    if let Ok(amt) = s.parse::<SignedAmount>() {
+        assert_eq!(amt.to_string().parse::<SignedAmount>(), Ok(amt));
+    }
+    let _ = Amount::from_sat_str(s);
+});
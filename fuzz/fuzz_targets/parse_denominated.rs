@@ -0,0 +1,16 @@
+//! Parses arbitrary strings as amounts followed by their denomination,
+//! whatever parses must format back to an equal amount in any denomination.
+
+#![no_main]
+
+use bitcoin_amount::{Denomination, SignedAmount};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|s: &str| {
+    if let Ok(amt) = SignedAmount::from_str_with_denomination(s) {
+        for &denom in Denomination::ALL.iter() {
+            let formatted = amt.display_in(denom).show_denomination(true).to_string();
+            assert_eq!(SignedAmount::from_str_with_denomination(&formatted), Ok(amt));
+        }
+    }
+});
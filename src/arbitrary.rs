@@ -0,0 +1,74 @@
+//! `arbitrary::Arbitrary` implementations of amounts, for fuzzing.
+//!
+//! Arbitrary amounts cover their whole range, overflows included, even with
+//! the `strict-money` feature, whose assertion then fails in the code under
+//! test rather than while generating the input. Fuzz targets that only want
+//! amounts valid by consensus can use [`valid_money`] and
+//! [`valid_signed_money`] with the `#[arbitrary(with = ...)]` attribute of
+//! the derive:
+//!
+//! ```ignore
+//! #[derive(Arbitrary)]
+//! struct Payment {
+//!     #[arbitrary(with = bitcoin_amount::arbitrary::valid_money)]
+//!     amount: Amount,
+//! }
+//! ```
+
+use arbitrary_crate::{Arbitrary, Result, Unstructured};
+
+use {Amount, SignedAmount};
+
+/// Generates an amount within the valid money range, `0..=MAX_MONEY`.
+pub fn valid_money(u: &mut Unstructured) -> Result<Amount> {
+    u.int_in_range(0..=Amount::MAX_MONEY.into_inner()).map(Amount::from_sat)
+}
+
+/// Generates a signed amount within the valid money range,
+/// `-MAX_MONEY..=MAX_MONEY`.
+pub fn valid_signed_money(u: &mut Unstructured) -> Result<SignedAmount> {
+    let max = SignedAmount::MAX_MONEY.into_inner();
+    u.int_in_range(-max..=max).map(SignedAmount::from_sat)
+}
+
+macro_rules! impl_arbitrary {
+    ($ty:ident, $inner:ty) => {
+        impl<'a> Arbitrary<'a> for $ty {
+            fn arbitrary(u: &mut Unstructured<'a>) -> Result<$ty> {
+                <$inner>::arbitrary(u).map($ty::from_sat_unasserted)
+            }
+
+            fn size_hint(depth: usize) -> (usize, Option<usize>) {
+                <$inner>::size_hint(depth)
+            }
+        }
+    };
+}
+
+impl_arbitrary!(Amount, u64);
+impl_arbitrary!(SignedAmount, i64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arbitrary_amounts() {
+        let data = [0xff; 16];
+        let mut u = Unstructured::new(&data);
+        assert!(valid_money(&mut u).unwrap().is_valid_money());
+        assert!(valid_signed_money(&mut u).unwrap().is_valid_money());
+
+        let data = [0x01, 0, 0, 0, 0, 0, 0, 0];
+        let mut u = Unstructured::new(&data);
+        assert_eq!(Amount::arbitrary(&mut u), Ok(Amount::from_sat(1)));
+        assert_eq!(Amount::size_hint(0), (8, Some(8)));
+    }
+
+    #[test]
+    fn arbitrary_out_of_range() {
+        let data = [0xff; 8];
+        let mut u = Unstructured::new(&data);
+        assert_eq!(Amount::arbitrary(&mut u).map(Amount::into_inner), Ok(u64::MAX));
+    }
+}
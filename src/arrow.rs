@@ -0,0 +1,147 @@
+//! Conversions between amounts and Arrow arrays.
+//!
+//! Amounts map to `Int64Array`s of satoshis, or to `Decimal128Array`s of BTC
+//! with precision 16 and scale 8, whose raw values are satoshis too. Nulls
+//! map to `None`.
+
+use std::convert::TryFrom;
+
+use arrow_array::{Decimal128Array, Int64Array};
+
+use {Amount, OutOfRangeError, ParseAmountError, SignedAmount};
+
+/// The precision of the `Decimal128` BTC mapping, enough for 99,999,999 BTC.
+pub const DECIMAL128_PRECISION: u8 = 16;
+/// The scale of the `Decimal128` BTC mapping, one satoshi.
+pub const DECIMAL128_SCALE: i8 = 8;
+
+/// The largest number of satoshis representable with `DECIMAL128_PRECISION`.
+const MAX_DECIMAL128_SAT: i128 = 9_999_999_999_999_999;
+
+macro_rules! impl_arrow {
+    ($ty:ident) => {
+        impl $ty {
+            /// Collects amounts, or `None` for nulls, into an `Int64Array` of
+            /// satoshis.
+            pub fn to_int64_array<I>(amounts: I) -> Result<Int64Array, OutOfRangeError>
+            where
+                I: IntoIterator,
+                I::Item: Into<Option<$ty>>,
+            {
+                amounts.into_iter()
+                    .map(|amt| amt.into().map(|amt| {
+                        i64::try_from(i128::from(amt)).map_err(|_| OutOfRangeError(()))
+                    }).transpose())
+                    .collect()
+            }
+
+            /// Reads an `Int64Array` of satoshis, nulls being `None`.
+            pub fn from_int64_array(array: &Int64Array)
+                -> Result<Vec<Option<$ty>>, OutOfRangeError>
+            {
+                array.iter()
+                    .map(|sat| sat.map(|sat| $ty::try_from(i128::from(sat))).transpose())
+                    .collect()
+            }
+
+            /// Collects amounts, or `None` for nulls, into a
+            /// `Decimal128Array` of BTC with precision 16 and scale 8.
+            ///
+            /// Returns an error if an amount is 100,000,000 BTC or more.
+            pub fn to_decimal128_array<I>(amounts: I) -> Result<Decimal128Array, OutOfRangeError>
+            where
+                I: IntoIterator,
+                I::Item: Into<Option<$ty>>,
+            {
+                let array = amounts.into_iter()
+                    .map(|amt| match amt.into().map(i128::from) {
+                        Some(sat) if sat.abs() > MAX_DECIMAL128_SAT => Err(OutOfRangeError(())),
+                        sat => Ok(sat),
+                    })
+                    .collect::<Result<Decimal128Array, OutOfRangeError>>()?;
+                Ok(array.with_precision_and_scale(DECIMAL128_PRECISION, DECIMAL128_SCALE)
+                    .expect("valid Decimal128 precision and scale"))
+            }
+
+            /// Reads a `Decimal128Array` of BTC exactly, nulls being `None`.
+            ///
+            /// Arrays of any scale are accepted, values with sub-satoshi
+            /// precision are rejected.
+            pub fn from_decimal128_array(array: &Decimal128Array)
+                -> Result<Vec<Option<$ty>>, ParseAmountError>
+            {
+                let scale = array.scale();
+                let to_amount = |value| {
+                    let sat = rescale_to_sat(value, scale)?;
+                    $ty::try_from(sat).map_err(|_| {
+                        if sat < 0 && $ty::min_value().is_zero() {
+                            ParseAmountError::Negative
+                        } else {
+                            ParseAmountError::OutOfRange
+                        }
+                    })
+                };
+                array.iter().map(|value| value.map(to_amount).transpose()).collect()
+            }
+        }
+    };
+}
+
+impl_arrow!(Amount);
+impl_arrow!(SignedAmount);
+
+/// Converts a decimal `value` with `scale` decimal places to satoshis.
+fn rescale_to_sat(value: i128, scale: i8) -> Result<i128, ParseAmountError> {
+    let shift = i32::from(DECIMAL128_SCALE) - i32::from(scale);
+    let factor = 10i128.checked_pow(shift.unsigned_abs())
+        .ok_or(ParseAmountError::OutOfRange)?;
+    if shift >= 0 {
+        value.checked_mul(factor).ok_or(ParseAmountError::OutOfRange)
+    } else if value % factor == 0 {
+        Ok(value / factor)
+    } else {
+        Err(ParseAmountError::TooPrecise)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{Array, Decimal128Array};
+
+    use {Amount, ParseAmountError, SignedAmount};
+
+    #[test]
+    fn arrow_int64() {
+        let amounts = vec![Amount::from_sat(253583), Amount::zero()];
+        let array = Amount::to_int64_array(amounts.clone()).unwrap();
+        assert_eq!(array.values(), &[253583, 0]);
+        let read = Amount::from_int64_array(&array).unwrap();
+        assert_eq!(read, vec![Some(Amount::from_sat(253583)), Some(Amount::zero())]);
+
+        let array = SignedAmount::to_int64_array(vec![Some(SignedAmount::from_sat(-1)), None]).unwrap();
+        assert!(array.is_null(1));
+        assert_eq!(SignedAmount::from_int64_array(&array).unwrap(),
+                   vec![Some(SignedAmount::from_sat(-1)), None]);
+        assert!(Amount::from_int64_array(&array).is_err());
+        assert!(Amount::to_int64_array(vec![Amount::max_value()]).is_err());
+    }
+
+    #[test]
+    fn arrow_decimal128() {
+        let amounts = vec![Some(Amount::from_sat(2_099_999_999_999_999)), None];
+        let array = Amount::to_decimal128_array(amounts.clone()).unwrap();
+        assert_eq!((array.precision(), array.scale()), (16, 8));
+        assert_eq!(array.value_as_string(0), "20999999.99999999");
+        assert_eq!(Amount::from_decimal128_array(&array).unwrap(), amounts);
+
+        let array = Decimal128Array::from(vec![-25, 1])
+            .with_precision_and_scale(10, 2).unwrap();
+        assert_eq!(SignedAmount::from_decimal128_array(&array).unwrap(),
+                   vec![Some(SignedAmount::from_sat(-25_000_000)), Some(SignedAmount::from_sat(1_000_000))]);
+        assert_eq!(Amount::from_decimal128_array(&array), Err(ParseAmountError::Negative));
+
+        let array = Decimal128Array::from(vec![1]).with_precision_and_scale(10, 9).unwrap();
+        assert_eq!(Amount::from_decimal128_array(&array), Err(ParseAmountError::TooPrecise));
+        assert!(Amount::to_decimal128_array(vec![Amount::max_value()]).is_err());
+    }
+}
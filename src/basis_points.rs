@@ -0,0 +1,100 @@
+//! Basis points, for expressing fees and rates on amounts.
+
+use core::convert::TryFrom;
+use core::ops::Mul;
+
+use {div_rounded, Amount, Rounding, SignedAmount};
+
+/// The amount of basis points in a whole (100%).
+pub const BPS_PER_UNIT: u32 = 10_000;
+
+/// A rate expressed in basis points, a hundredth of a percent.
+///
+/// Multiplying an `Amount` by `BasisPoints` gives the fraction of the amount
+/// corresponding to the rate, e.g. a 25 bps fee on a 1 BTC trade is 0.0025 BTC.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct BasisPoints(u32);
+
+impl BasisPoints {
+    /// Creates a new `BasisPoints` rate.
+    pub fn new(bps: u32) -> BasisPoints {
+        BasisPoints(bps)
+    }
+
+    /// Creates a new `BasisPoints` rate from a whole percentage.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rate doesn't fit in an `u32` of basis points.
+    pub fn from_percent(percent: u32) -> BasisPoints {
+        BasisPoints(percent.checked_mul(100).expect("BasisPoints overflow"))
+    }
+
+    /// Converts these `BasisPoints` to the inner value.
+    pub fn into_inner(self) -> u32 {
+        self.0
+    }
+}
+
+macro_rules! impl_mul_basis_points {
+    ($ty:ident) => {
+        impl $ty {
+            /// Computes the fraction of this amount given by `bps`, rounding
+            /// the result to a whole satoshi as specified by `rounding`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the result doesn't fit in the amount type, which can
+            /// only happen for rates greater than 100%.
+            pub fn mul_basis_points(self, bps: BasisPoints, rounding: Rounding) -> $ty {
+                let num = i128::from(self.into_inner()) * i128::from(bps.0);
+                div_rounded(num, i128::from(BPS_PER_UNIT), rounding)
+                    .and_then(|sat| TryFrom::try_from(sat).ok())
+                    .map($ty::from_sat)
+                    .expect(concat!(stringify!($ty), " multiplication overflow"))
+            }
+        }
+
+        /// Rounds to the nearest satoshi, see `mul_basis_points` to choose
+        /// the rounding direction.
+        impl Mul<BasisPoints> for $ty {
+            type Output = $ty;
+
+            fn mul(self, rhs: BasisPoints) -> Self::Output {
+                self.mul_basis_points(rhs, Rounding::Nearest)
+            }
+        }
+    };
+}
+
+impl_mul_basis_points!(Amount);
+impl_mul_basis_points!(SignedAmount);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basis_points_mul() {
+        let trade = Amount::from_sat(100_000_000);
+        assert_eq!(trade * BasisPoints::new(25), Amount::from_sat(250_000));
+        assert_eq!(trade * BasisPoints::from_percent(100), trade);
+        assert_eq!(trade * BasisPoints::new(0), Amount::zero());
+    }
+
+    #[test]
+    fn basis_points_rounding() {
+        let amt = Amount::from_sat(1_999);
+        let bps = BasisPoints::new(25);
+        assert_eq!(amt.mul_basis_points(bps, Rounding::Floor), Amount::from_sat(4));
+        assert_eq!(amt.mul_basis_points(bps, Rounding::Ceil), Amount::from_sat(5));
+        assert_eq!(amt.mul_basis_points(bps, Rounding::Nearest), Amount::from_sat(5));
+        assert_eq!(Amount::from_sat(1_000).mul_basis_points(bps, Rounding::Nearest),
+                   Amount::from_sat(3));
+
+        let neg = SignedAmount::from_sat(-1_999);
+        assert_eq!(neg.mul_basis_points(bps, Rounding::Floor), SignedAmount::from_sat(-5));
+        assert_eq!(neg.mul_basis_points(bps, Rounding::Ceil), SignedAmount::from_sat(-4));
+        assert_eq!(neg.mul_basis_points(bps, Rounding::Nearest), SignedAmount::from_sat(-5));
+    }
+}
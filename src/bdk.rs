@@ -0,0 +1,87 @@
+//! Conversions from and to the balances of BDK wallets.
+//!
+//! BDK amounts are the ones of the `bitcoin` crate, which convert with the
+//! `bitcoin` feature. [`Balance`] mirrors the BDK one with `Amount` fields so
+//! wallets can format and parse the balance they show without converting each
+//! field by hand.
+
+use std::convert::{TryFrom, TryInto};
+
+use bdk_chain;
+
+use {Amount, OutOfRangeError};
+
+/// The balance of a wallet, by category, like the BDK `Balance`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Balance {
+    /// The coinbase outputs that haven't matured yet.
+    pub immature: Amount,
+    /// The unconfirmed outputs of transactions made by the wallet.
+    pub trusted_pending: Amount,
+    /// The unconfirmed outputs received from other wallets.
+    pub untrusted_pending: Amount,
+    /// The confirmed outputs, spendable right away.
+    pub confirmed: Amount,
+}
+
+impl Balance {
+    /// Returns the confirmed and trusted pending balance, which can be spent
+    /// without the risk of another party double spending it.
+    pub fn trusted_spendable(&self) -> Amount {
+        self.confirmed + self.trusted_pending
+    }
+
+    /// Returns the whole balance of the wallet.
+    pub fn total(&self) -> Amount {
+        self.confirmed + self.trusted_pending + self.untrusted_pending + self.immature
+    }
+}
+
+/// Fails only with the `strict-money` feature enabled, if a field isn't
+/// valid money.
+impl TryFrom<bdk_chain::Balance> for Balance {
+    type Error = OutOfRangeError;
+
+    fn try_from(balance: bdk_chain::Balance) -> Result<Balance, OutOfRangeError> {
+        Ok(Balance {
+            immature: balance.immature.try_into()?,
+            trusted_pending: balance.trusted_pending.try_into()?,
+            untrusted_pending: balance.untrusted_pending.try_into()?,
+            confirmed: balance.confirmed.try_into()?,
+        })
+    }
+}
+
+impl From<Balance> for bdk_chain::Balance {
+    fn from(balance: Balance) -> bdk_chain::Balance {
+        bdk_chain::Balance {
+            immature: balance.immature.into(),
+            trusted_pending: balance.trusted_pending.into(),
+            untrusted_pending: balance.untrusted_pending.into(),
+            confirmed: balance.confirmed.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin_crate;
+
+    #[test]
+    fn bdk_balance() {
+        let bdk_balance = bdk_chain::Balance {
+            immature: bitcoin_crate::Amount::from_sat(1),
+            trusted_pending: bitcoin_crate::Amount::from_sat(20),
+            untrusted_pending: bitcoin_crate::Amount::from_sat(300),
+            confirmed: bitcoin_crate::Amount::from_sat(4_000),
+        };
+
+        let balance = Balance::try_from(bdk_balance.clone()).unwrap();
+        assert_eq!(balance.confirmed, Amount::from_sat(4_000));
+        assert_eq!(balance.trusted_spendable(), Amount::from_sat(4_020));
+        assert_eq!(balance.total(), Amount::from_sat(4_321));
+        assert_eq!(Amount::try_from(bdk_balance.total()), Ok(balance.total()));
+        assert_eq!(bdk_chain::Balance::from(balance), bdk_balance);
+    }
+}
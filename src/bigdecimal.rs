@@ -0,0 +1,112 @@
+//! Conversions between amounts and `bigdecimal` numbers of BTC.
+//!
+//! Converting an amount to a `BigDecimal` is exact, with a scale of 8, for
+//! arbitrary precision intermediate arithmetic. Converting the result back
+//! is checked: sub-satoshi precision is rejected instead of rounded, as are
+//! values that don't fit in the amount type.
+
+use std::convert::TryFrom;
+
+use bigdecimal_crate::num_bigint::BigInt;
+use bigdecimal_crate::{BigDecimal, ToPrimitive};
+
+use {Amount, ParseAmountError, SignedAmount};
+
+/// The scale of BTC `BigDecimal`s, one satoshi.
+const SCALE: i64 = 8;
+/// More decimal digits than any `i128` has.
+const MAX_DIGITS: i64 = 40;
+
+/// Converts a `BigDecimal` number of BTC to satoshis exactly.
+fn bigdecimal_to_sat(btc: &BigDecimal) -> Result<i128, ParseAmountError> {
+    // Once normalized the digits have no trailing zeros, so a scale past a
+    // satoshi means sub-satoshi precision.
+    let (digits, scale) = btc.normalized().into_bigint_and_exponent();
+    if scale > SCALE {
+        return Err(ParseAmountError::TooPrecise);
+    }
+    let digits = digits.to_i128().ok_or(ParseAmountError::OutOfRange)?;
+    if digits == 0 {
+        return Ok(0);
+    }
+    if SCALE - scale > MAX_DIGITS {
+        return Err(ParseAmountError::OutOfRange);
+    }
+    10i128.checked_pow((SCALE - scale) as u32)
+        .and_then(|shift| digits.checked_mul(shift))
+        .ok_or(ParseAmountError::OutOfRange)
+}
+
+macro_rules! impl_bigdecimal {
+    ($ty:ident) => {
+        /// Converts the amount to a number of BTC with 8 decimal places, e.g.
+        /// `0.00253583`.
+        impl From<$ty> for BigDecimal {
+            fn from(amt: $ty) -> BigDecimal {
+                BigDecimal::new(BigInt::from(i128::from(amt)), SCALE)
+            }
+        }
+
+        /// Converts a number of BTC exactly, sub-satoshi precision is
+        /// rejected.
+        impl<'a> TryFrom<&'a BigDecimal> for $ty {
+            type Error = ParseAmountError;
+
+            fn try_from(btc: &'a BigDecimal) -> Result<$ty, ParseAmountError> {
+                let sat = bigdecimal_to_sat(btc)?;
+                if sat < 0 && $ty::min_value().is_zero() {
+                    return Err(ParseAmountError::Negative);
+                }
+                $ty::try_from(sat).map_err(|_| ParseAmountError::OutOfRange)
+            }
+        }
+
+        /// Converts a number of BTC exactly, sub-satoshi precision is
+        /// rejected.
+        impl TryFrom<BigDecimal> for $ty {
+            type Error = ParseAmountError;
+
+            fn try_from(btc: BigDecimal) -> Result<$ty, ParseAmountError> {
+                $ty::try_from(&btc)
+            }
+        }
+    };
+}
+
+impl_bigdecimal!(Amount);
+impl_bigdecimal!(SignedAmount);
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+    use std::str::FromStr;
+
+    use bigdecimal_crate::BigDecimal;
+
+    use {Amount, ParseAmountError, SignedAmount};
+
+    #[test]
+    fn bigdecimal_conversions() {
+        let dec = |s: &str| BigDecimal::from_str(s).unwrap();
+        assert_eq!(BigDecimal::from(Amount::from_sat(253583)).to_string(), "0.00253583");
+        assert_eq!(BigDecimal::from(SignedAmount::from_sat(-100_000_000)), dec("-1"));
+
+        assert_eq!(Amount::try_from(dec("0.00253583")), Ok(Amount::from_sat(253583)));
+        assert_eq!(Amount::try_from(&dec("21")), Ok(Amount::from_sat(2_100_000_000)));
+        assert_eq!(Amount::try_from(dec("1e-8")), Ok(Amount::from_sat(1)));
+        assert_eq!(Amount::try_from(dec("0.000000010000000000")), Ok(Amount::from_sat(1)));
+        assert_eq!(Amount::try_from(dec("0e1000")), Ok(Amount::zero()));
+        assert_eq!(SignedAmount::try_from(dec("-0.5")), Ok(SignedAmount::from_sat(-50_000_000)));
+
+        // e.g. the result of a division landing back in satoshis.
+        let share = BigDecimal::from(Amount::from_sat(100_000_000)) / BigDecimal::from(4);
+        assert_eq!(Amount::try_from(share), Ok(Amount::from_sat(25_000_000)));
+        let share = BigDecimal::from(Amount::from_sat(100_000_000)) / BigDecimal::from(3);
+        assert_eq!(Amount::try_from(share), Err(ParseAmountError::TooPrecise));
+
+        assert_eq!(Amount::try_from(dec("1e-1000000")), Err(ParseAmountError::TooPrecise));
+        assert_eq!(Amount::try_from(dec("-1")), Err(ParseAmountError::Negative));
+        assert_eq!(Amount::try_from(dec("1e1000000")), Err(ParseAmountError::OutOfRange));
+        assert_eq!(SignedAmount::try_from(dec("100000000000")), Err(ParseAmountError::OutOfRange));
+    }
+}
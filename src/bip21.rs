@@ -0,0 +1,89 @@
+//! The `amount` parameter of BIP-21 `bitcoin:` URIs.
+//!
+//! BIP-21 amounts are decimal numbers of BTC, e.g. `amount=20.3`, without a
+//! sign, exponent or digit grouping.
+
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+
+use {Amount, AmountParser, ParseAmountError};
+#[cfg(feature = "alloc")]
+use Denomination;
+
+/// The most decimal places of a BIP-21 amount, a satoshi.
+const MAX_DECIMALS: usize = 8;
+
+/// Parses the value of an `amount` parameter, e.g. `20.3`.
+///
+/// Besides the characters the BIP-21 grammar rejects, more than 8 decimal
+/// places are rejected even if they are zeros, as are amounts above
+/// `Amount::MAX_MONEY`.
+pub fn parse_amount(s: &str) -> Result<Amount, ParseAmountError> {
+    if s.starts_with('-') {
+        return Err(ParseAmountError::Negative);
+    }
+    let amount = AmountParser::new()
+        .allow_negative(false)
+        .allow_plus(false)
+        .allow_exponent(false)
+        .max_decimals(MAX_DECIMALS)
+        .parse_amount(s)?;
+    if amount > Amount::MAX_MONEY {
+        return Err(ParseAmountError::OutOfRange);
+    }
+    Ok(amount)
+}
+
+/// Formats the value of an `amount` parameter, without trailing zeros, e.g.
+/// `20.3` or `50`.
+#[cfg(feature = "alloc")]
+pub fn format_amount(amount: Amount) -> String {
+    amount.display_in(Denomination::Bitcoin).trim_trailing_zeros(true).to_string()
+}
+
+/// Finds and parses the `amount` parameter of a `bitcoin:` URI, `None` if
+/// it doesn't have one, e.g. `bitcoin:175tWpb8K1S7NmH4Zx6rewF9WQrcZv245W?amount=20.3`.
+pub fn uri_amount(uri: &str) -> Option<Result<Amount, ParseAmountError>> {
+    let query = &uri[uri.find('?')? + 1..];
+    query.split('&')
+        .find_map(|param| param.strip_prefix("amount="))
+        .map(parse_amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn bip21_amounts() {
+        assert_eq!(parse_amount("20.3"), Ok(Amount::from_sat(2_030_000_000)));
+        assert_eq!(parse_amount("50"), Ok(Amount::from_sat(5_000_000_000)));
+        assert_eq!(parse_amount("0.00000001"), Ok(Amount::from_sat(1)));
+        assert_eq!(parse_amount(".5"), Ok(Amount::from_sat(50_000_000)));
+
+        assert_eq!(parse_amount(""), Err(ParseAmountError::Empty));
+        assert_eq!(parse_amount("-1"), Err(ParseAmountError::Negative));
+        assert_eq!(parse_amount("-0"), Err(ParseAmountError::Negative));
+        assert_eq!(parse_amount("+1"), Err(ParseAmountError::InvalidCharacter { pos: 0 }));
+        assert_eq!(parse_amount("1e3"), Err(ParseAmountError::InvalidCharacter { pos: 1 }));
+        assert_eq!(parse_amount("1,000"), Err(ParseAmountError::InvalidCharacter { pos: 1 }));
+        assert_eq!(parse_amount("0.000000010"), Err(ParseAmountError::TooPrecise));
+        assert_eq!(parse_amount("21000001"), Err(ParseAmountError::OutOfRange));
+
+        assert_eq!(format_amount(Amount::from_sat(2_030_000_000)), "20.3");
+        assert_eq!(format_amount(Amount::from_sat(5_000_000_000)), "50");
+        assert_eq!(format_amount(Amount::from_sat(1)), "0.00000001");
+        assert_eq!(format_amount(Amount::zero()), "0");
+    }
+
+    #[test]
+    fn bip21_uri_amount() {
+        let uri = "bitcoin:175tWpb8K1S7NmH4Zx6rewF9WQrcZv245W?label=Luke-Jr&amount=20.3";
+        assert_eq!(uri_amount(uri), Some(Ok(Amount::from_sat(2_030_000_000))));
+        assert_eq!(uri_amount("bitcoin:175tWpb8K1S7NmH4Zx6rewF9WQrcZv245W?label=Luke-Jr"), None);
+        assert_eq!(uri_amount("bitcoin:175tWpb8K1S7NmH4Zx6rewF9WQrcZv245W"), None);
+        assert_eq!(uri_amount("bitcoin:175tWpb8K1S7NmH4Zx6rewF9WQrcZv245W?amount=1e3"),
+                   Some(Err(ParseAmountError::InvalidCharacter { pos: 1 })));
+    }
+}
@@ -0,0 +1,103 @@
+//! Conversions between amounts and the amounts of the `bitcoin` crate.
+//!
+//! Amounts of the same signedness convert losslessly both ways, conversions
+//! between signed and unsigned amounts fail out of range, like the ones
+//! between `Amount` and `SignedAmount`. With the `strict-money` feature
+//! enabled, conversions to the amounts of this crate also fail out of the
+//! valid money range, which the `bitcoin` amounts don't check.
+
+use std::convert::TryFrom;
+
+use bitcoin_crate;
+
+use {Amount, NegativeAmountError, OutOfRangeError, SignedAmount};
+
+impl TryFrom<bitcoin_crate::Amount> for Amount {
+    type Error = OutOfRangeError;
+
+    fn try_from(amt: bitcoin_crate::Amount) -> Result<Amount, OutOfRangeError> {
+        Amount::try_from_sat(amt.to_sat())
+    }
+}
+
+impl From<Amount> for bitcoin_crate::Amount {
+    fn from(amt: Amount) -> bitcoin_crate::Amount {
+        bitcoin_crate::Amount::from_sat(amt.into_inner())
+    }
+}
+
+impl TryFrom<bitcoin_crate::SignedAmount> for SignedAmount {
+    type Error = OutOfRangeError;
+
+    fn try_from(amt: bitcoin_crate::SignedAmount) -> Result<SignedAmount, OutOfRangeError> {
+        SignedAmount::try_from_sat(amt.to_sat())
+    }
+}
+
+impl From<SignedAmount> for bitcoin_crate::SignedAmount {
+    fn from(amt: SignedAmount) -> bitcoin_crate::SignedAmount {
+        bitcoin_crate::SignedAmount::from_sat(amt.into_inner())
+    }
+}
+
+impl TryFrom<bitcoin_crate::SignedAmount> for Amount {
+    type Error = OutOfRangeError;
+
+    fn try_from(amt: bitcoin_crate::SignedAmount) -> Result<Amount, OutOfRangeError> {
+        Amount::try_from(amt.to_sat())
+    }
+}
+
+impl TryFrom<Amount> for bitcoin_crate::SignedAmount {
+    type Error = OutOfRangeError;
+
+    fn try_from(amt: Amount) -> Result<bitcoin_crate::SignedAmount, OutOfRangeError> {
+        amt.to_signed().map(bitcoin_crate::SignedAmount::from)
+    }
+}
+
+impl TryFrom<bitcoin_crate::Amount> for SignedAmount {
+    type Error = OutOfRangeError;
+
+    fn try_from(amt: bitcoin_crate::Amount) -> Result<SignedAmount, OutOfRangeError> {
+        SignedAmount::try_from(amt.to_sat())
+    }
+}
+
+impl TryFrom<SignedAmount> for bitcoin_crate::Amount {
+    type Error = NegativeAmountError;
+
+    fn try_from(amt: SignedAmount) -> Result<bitcoin_crate::Amount, NegativeAmountError> {
+        amt.to_unsigned().map(bitcoin_crate::Amount::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use bitcoin_crate;
+
+    use {Amount, SignedAmount};
+
+    #[test]
+    fn bitcoin_conversions() {
+        let amount = Amount::from_sat(253583);
+        let btc_amount = bitcoin_crate::Amount::from_sat(253583);
+        assert_eq!(bitcoin_crate::Amount::from(amount), btc_amount);
+        assert_eq!(Amount::try_from(btc_amount), Ok(amount));
+        assert_eq!(bitcoin_crate::SignedAmount::try_from(amount),
+                   Ok(bitcoin_crate::SignedAmount::from_sat(253583)));
+        assert_eq!(SignedAmount::try_from(btc_amount), Ok(SignedAmount::from_sat(253583)));
+
+        let amount = SignedAmount::from_sat(-1);
+        let btc_amount = bitcoin_crate::SignedAmount::from_sat(-1);
+        assert_eq!(bitcoin_crate::SignedAmount::from(amount), btc_amount);
+        assert_eq!(SignedAmount::try_from(btc_amount), Ok(amount));
+        assert!(bitcoin_crate::Amount::try_from(amount).is_err());
+        assert!(Amount::try_from(btc_amount).is_err());
+        assert!(SignedAmount::try_from(bitcoin_crate::Amount::from_sat(u64::MAX)).is_err());
+        #[cfg(feature = "strict-money")]
+        assert!(Amount::try_from(bitcoin_crate::Amount::from_sat(u64::MAX)).is_err());
+    }
+}
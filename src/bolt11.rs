@@ -0,0 +1,80 @@
+//! The amounts of BOLT11 Lightning invoices.
+//!
+//! An invoice amount is an integer number of BTC followed by an optional
+//! multiplier, `m` (milli), `u` (micro), `n` (nano) or `p` (pico), e.g.
+//! `2500u` for 0.0025 BTC. Amounts are converted exactly, those with
+//! sub-millisatoshi precision, e.g. `1p`, are rejected.
+
+use {Amount, MilliSatoshi, ParseAmountError, MSAT_PER_SAT};
+
+/// Parses an invoice amount, e.g. `2500u`, as millisatoshis.
+pub fn parse_msat(s: &str) -> Result<MilliSatoshi, ParseAmountError> {
+    let (digits, multiplier) = match s.char_indices().last() {
+        Some((pos, c)) if c.is_ascii_alphabetic() => (&s[..pos], Some((pos, c))),
+        _ => (s, None),
+    };
+    if digits.is_empty() {
+        return Err(ParseAmountError::Empty);
+    }
+    if let Some(pos) = digits.bytes().position(|b| !b.is_ascii_digit()) {
+        return Err(ParseAmountError::InvalidCharacter { pos });
+    }
+    let n = digits.parse::<u64>().map_err(|_| ParseAmountError::OutOfRange)?;
+
+    // The millisatoshis per unit of the multiplier, pico-BTC being a tenth
+    // of a millisatoshi.
+    let msat_per_unit = match multiplier {
+        None => 100_000_000_000,
+        Some((_, 'm')) => 100_000_000,
+        Some((_, 'u')) => 100_000,
+        Some((_, 'n')) => 100,
+        Some((_, 'p')) => {
+            if n % 10 != 0 {
+                return Err(ParseAmountError::TooPrecise);
+            }
+            return Ok(MilliSatoshi::from_msat(n / 10));
+        }
+        Some((pos, _)) => return Err(ParseAmountError::InvalidCharacter { pos }),
+    };
+    n.checked_mul(msat_per_unit).map(MilliSatoshi::from_msat).ok_or(ParseAmountError::OutOfRange)
+}
+
+/// Parses an invoice amount, e.g. `2500u`, as an `Amount`. Amounts that
+/// aren't a whole number of satoshis, e.g. `1n`, are rejected.
+pub fn parse_amount(s: &str) -> Result<Amount, ParseAmountError> {
+    let msat = parse_msat(s)?.into_inner();
+    if msat % MSAT_PER_SAT != 0 {
+        return Err(ParseAmountError::TooPrecise);
+    }
+    Amount::try_from_sat(msat / MSAT_PER_SAT).map_err(|_| ParseAmountError::OutOfRange)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bolt11_amounts() {
+        assert_eq!(parse_msat("2500u"), Ok(MilliSatoshi::from_msat(250_000_000)));
+        assert_eq!(parse_amount("2500u"), Ok(Amount::from_sat(250_000)));
+        assert_eq!(parse_msat("20m"), Ok(MilliSatoshi::from_msat(2_000_000_000)));
+        assert_eq!(parse_msat("1n"), Ok(MilliSatoshi::from_msat(100)));
+        assert_eq!(parse_msat("10p"), Ok(MilliSatoshi::from_msat(1)));
+        assert_eq!(parse_msat("1"), Ok(MilliSatoshi::from_msat(100_000_000_000)));
+        assert_eq!(parse_amount("10n"), Ok(Amount::from_sat(1)));
+
+        assert_eq!(parse_msat("1p"), Err(ParseAmountError::TooPrecise));
+        assert_eq!(parse_amount("1n"), Err(ParseAmountError::TooPrecise));
+        assert_eq!(parse_msat(""), Err(ParseAmountError::Empty));
+        assert_eq!(parse_msat("m"), Err(ParseAmountError::Empty));
+        assert_eq!(parse_msat("25x"), Err(ParseAmountError::InvalidCharacter { pos: 2 }));
+        assert_eq!(parse_msat("2.5m"), Err(ParseAmountError::InvalidCharacter { pos: 1 }));
+        assert_eq!(parse_msat("-1m"), Err(ParseAmountError::InvalidCharacter { pos: 0 }));
+        assert_eq!(parse_msat("1000000000"), Err(ParseAmountError::OutOfRange));
+        assert_eq!(parse_msat("99999999999999999999p"), Err(ParseAmountError::OutOfRange));
+        #[cfg(feature = "strict-money")]
+        assert_eq!(parse_amount("100000000"), Err(ParseAmountError::OutOfRange));
+        #[cfg(not(feature = "strict-money"))]
+        assert_eq!(parse_amount("100000000"), Ok(Amount::from_sat(10_000_000_000_000_000)));
+    }
+}
@@ -0,0 +1,116 @@
+//! Conversions between amounts and BSON values.
+//!
+//! Amounts are stored as `Bson::Int64` satoshis, or as a `Decimal128` number
+//! of BTC where the unit must be explicit. Both round-trip losslessly.
+
+use std::convert::TryFrom;
+
+use bson_crate::{Bson, Decimal128};
+
+use {Amount, OutOfRangeError, ParseAmountError, SignedAmount};
+
+macro_rules! impl_bson {
+    ($ty:ident) => {
+        impl $ty {
+            /// Converts this amount to a `Decimal128` number of BTC with 8
+            /// decimal places, e.g. `0.00253583`.
+            pub fn to_decimal128(self) -> Decimal128 {
+                self.to_string().parse().expect("formatted amounts are valid Decimal128s")
+            }
+
+            /// Converts a `Decimal128` number of BTC exactly. Sub-satoshi
+            /// precision is rejected, as are NaN and infinities.
+            pub fn from_decimal128(btc: Decimal128) -> Result<$ty, ParseAmountError> {
+                btc.to_string().parse()
+            }
+        }
+
+        /// Converts a `Bson::Int64` or `Bson::Int32` number of satoshis, or
+        /// a `Bson::Decimal128` number of BTC.
+        impl<'a> TryFrom<&'a Bson> for $ty {
+            type Error = ParseAmountError;
+
+            fn try_from(bson: &'a Bson) -> Result<$ty, ParseAmountError> {
+                let sat = match *bson {
+                    Bson::Int64(sat) => sat,
+                    Bson::Int32(sat) => i64::from(sat),
+                    Bson::Decimal128(btc) => return $ty::from_decimal128(btc),
+                    _ => return Err(ParseAmountError::NotANumber),
+                };
+                if sat < 0 && $ty::min_value().is_zero() {
+                    return Err(ParseAmountError::Negative);
+                }
+                $ty::try_from(sat).map_err(|_| ParseAmountError::OutOfRange)
+            }
+        }
+
+        /// Converts a `Bson::Int64` or `Bson::Int32` number of satoshis, or
+        /// a `Bson::Decimal128` number of BTC.
+        impl TryFrom<Bson> for $ty {
+            type Error = ParseAmountError;
+
+            fn try_from(bson: Bson) -> Result<$ty, ParseAmountError> {
+                $ty::try_from(&bson)
+            }
+        }
+    };
+}
+
+impl_bson!(Amount);
+impl_bson!(SignedAmount);
+
+/// Converts the amount to a `Bson::Int64` number of satoshis.
+impl TryFrom<Amount> for Bson {
+    type Error = OutOfRangeError;
+
+    fn try_from(amt: Amount) -> Result<Bson, OutOfRangeError> {
+        i64::try_from(amt).map(Bson::Int64)
+    }
+}
+
+/// Converts the amount to a `Bson::Int64` number of satoshis.
+impl From<SignedAmount> for Bson {
+    fn from(amt: SignedAmount) -> Bson {
+        Bson::Int64(amt.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use bson_crate::{Bson, Decimal128};
+
+    use {Amount, ParseAmountError, SignedAmount};
+
+    #[test]
+    fn bson_int64() {
+        let bson = Bson::try_from(Amount::from_sat(253583)).unwrap();
+        assert_eq!(bson, Bson::Int64(253583));
+        assert_eq!(Amount::try_from(&bson), Ok(Amount::from_sat(253583)));
+        assert_eq!(Bson::from(SignedAmount::from_sat(-1)), Bson::Int64(-1));
+        assert_eq!(SignedAmount::try_from(Bson::Int64(-1)), Ok(SignedAmount::from_sat(-1)));
+        assert_eq!(Amount::try_from(Bson::Int32(546)), Ok(Amount::from_sat(546)));
+        assert_eq!(Amount::try_from(Bson::Int64(-1)), Err(ParseAmountError::Negative));
+        assert_eq!(Amount::try_from(Bson::String("1".to_owned())), Err(ParseAmountError::NotANumber));
+        assert!(Bson::try_from(Amount::max_value()).is_err());
+    }
+
+    #[test]
+    fn bson_decimal128() {
+        let amounts = [0, 1, 253583, 100_000_000, 2_099_999_999_999_999];
+        for &sat in &amounts {
+            let amount = Amount::from_sat(sat);
+            assert_eq!(Amount::from_decimal128(amount.to_decimal128()), Ok(amount));
+            assert_eq!(Amount::try_from(Bson::Decimal128(amount.to_decimal128())), Ok(amount));
+        }
+        let amount = SignedAmount::from_sat(-253583);
+        assert_eq!(amount.to_decimal128().to_string(), "-0.00253583");
+        assert_eq!(SignedAmount::from_decimal128(amount.to_decimal128()), Ok(amount));
+
+        let parse = |s: &str| Amount::from_decimal128(s.parse::<Decimal128>().unwrap());
+        assert_eq!(parse("1E-8"), Ok(Amount::from_sat(1)));
+        assert_eq!(parse("1E-9"), Err(ParseAmountError::TooPrecise));
+        assert!(parse("NaN").is_err());
+    }
+}
@@ -0,0 +1,43 @@
+//! The ratios between bitcoin units.
+//!
+//! `SAT_PER_BTC` is an `i64`, like it always was, the ratios added later are
+//! `u64`s like the `Amount` satoshis.
+
+pub use millisatoshi::MSAT_PER_SAT;
+pub use {SAT_PER_BTC, SAT_PER_BTC_FP};
+
+/// The amount of satoshis in a mBTC.
+pub const SAT_PER_MBTC: u64 = 100_000;
+
+/// The amount of satoshis in a μBTC.
+pub const SAT_PER_UBTC: u64 = 100;
+
+/// The amount of satoshis in a bit, the same as in a μBTC.
+pub const SAT_PER_BIT: u64 = SAT_PER_UBTC;
+
+/// The amount of millisatoshis in a BTC.
+pub const MSAT_PER_BTC: u64 = SAT_PER_BTC as u64 * MSAT_PER_SAT;
+
+/// The amount of mBTC in a BTC.
+pub const MBTC_PER_BTC: u64 = 1_000;
+
+/// The amount of μBTC in a BTC.
+pub const UBTC_PER_BTC: u64 = 1_000_000;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {Amount, Denomination};
+
+    #[test]
+    fn consts_ratios() {
+        let one = |denom| Amount::from_str_in("1", denom).unwrap().into_inner();
+        assert_eq!(one(Denomination::Bitcoin), SAT_PER_BTC as u64);
+        assert_eq!(one(Denomination::MilliBitcoin), SAT_PER_MBTC);
+        assert_eq!(one(Denomination::MicroBitcoin), SAT_PER_UBTC);
+        assert_eq!(one(Denomination::Bit), SAT_PER_BIT);
+        assert_eq!(MBTC_PER_BTC * SAT_PER_MBTC, SAT_PER_BTC as u64);
+        assert_eq!(UBTC_PER_BTC * SAT_PER_UBTC, SAT_PER_BTC as u64);
+        assert_eq!(Denomination::Bitcoin.msat_per_unit(), MSAT_PER_BTC);
+    }
+}
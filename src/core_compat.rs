@@ -0,0 +1,219 @@
+//! Bitcoin Core compatible parsing and formatting of amounts.
+//!
+//! `parse_money` and `format_money` accept and produce exactly the strings
+//! of `ParseMoney` and `FormatMoney` in Bitcoin Core's `util/moneystr.cpp`,
+//! e.g. for RPC clients and servers that must agree with `bitcoind` on user
+//! supplied amounts. The JSON-RPC numbers of `ValueFromAmount`, with a fixed
+//! 8 decimal places, are the `Display` of `Amount` and `SignedAmount`.
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use core::fmt::Write;
+
+use {Amount, ParseAmountError, SAT_PER_BTC};
+#[cfg(feature = "alloc")]
+use SignedAmount;
+
+/// The most whole BTC digits `ParseMoney` accepts, guarding against
+/// overflows.
+const MAX_WHOLE_DIGITS: usize = 10;
+/// The number of decimal places of a BTC amount.
+const DECIMALS: usize = 8;
+
+/// Whether `c` is one of the whitespace characters of Bitcoin Core's
+/// `IsSpace`.
+fn is_space(c: char) -> bool {
+    matches!(c, ' ' | '\x0c' | '\n' | '\r' | '\t' | '\x0b')
+}
+
+/// Parses a non-negative decimal number of BTC like Bitcoin Core's
+/// `ParseMoney`.
+///
+/// Surrounding whitespace is ignored, a sign, exponent or embedded
+/// whitespace isn't, and either the whole or the fractional digits can be
+/// missing, e.g. `.5` or `1.`, but not both unless a `.` is given. More than
+/// 8 decimal places are rejected even if they are zeros, as are more than
+/// 10 whole digits and amounts above `Amount::MAX_MONEY`.
+pub fn parse_money(s: &str) -> Result<Amount, ParseAmountError> {
+    if let Some(pos) = s.find('\0') {
+        return Err(ParseAmountError::InvalidCharacter { pos });
+    }
+    let offset = s.len() - s.trim_start_matches(is_space).len();
+    let s = s.trim_matches(is_space);
+    if s.is_empty() {
+        return Err(ParseAmountError::Empty);
+    }
+
+    let (whole, frac, frac_offset) = match s.find('.') {
+        Some(dot) => (&s[..dot], &s[dot + 1..], offset + dot + 1),
+        None => (s, "", offset + s.len()),
+    };
+    if let Some(pos) = whole.bytes().position(|b| !b.is_ascii_digit()) {
+        return Err(ParseAmountError::InvalidCharacter { pos: offset + pos });
+    }
+    let mut sat = 0;
+    for (pos, b) in frac.bytes().enumerate() {
+        if !b.is_ascii_digit() {
+            return Err(ParseAmountError::InvalidCharacter { pos: frac_offset + pos });
+        }
+        if pos == DECIMALS {
+            return Err(ParseAmountError::TooPrecise);
+        }
+        sat += u64::from(b - b'0') * 10u64.pow((DECIMALS - 1 - pos) as u32);
+    }
+    if whole.len() > MAX_WHOLE_DIGITS {
+        return Err(ParseAmountError::OutOfRange);
+    }
+
+    let whole = whole.bytes().fold(0, |acc, b| acc * 10 + u64::from(b - b'0'));
+    let sat = whole * SAT_PER_BTC as u64 + sat;
+    if sat > Amount::MAX_MONEY.into_inner() {
+        return Err(ParseAmountError::OutOfRange);
+    }
+    Ok(Amount::from_sat(sat))
+}
+
+/// Formats a number of BTC like Bitcoin Core's `FormatMoney`, with trailing
+/// zeros trimmed down to 2 decimal places, e.g. `0.0025` or `1.00`.
+#[cfg(feature = "alloc")]
+pub fn format_money(amount: Amount) -> String {
+    format_sat(false, amount.into_inner())
+}
+
+/// Formats a possibly negative number of BTC like Bitcoin Core's
+/// `FormatMoney`, e.g. `-0.0025`.
+#[cfg(feature = "alloc")]
+pub fn format_signed_money(amount: SignedAmount) -> String {
+    let sat = amount.into_inner();
+    format_sat(sat < 0, sat.unsigned_abs())
+}
+
+/// Formats `sat` satoshis as BTC, with at least 2 decimal places.
+#[cfg(feature = "alloc")]
+fn format_sat(negative: bool, sat: u64) -> String {
+    let per_btc = SAT_PER_BTC as u64;
+    let mut s = String::new();
+    write!(s, "{}{}.{:08}", if negative { "-" } else { "" }, sat / per_btc, sat % per_btc)
+        .expect("writing to a String can't fail");
+    let min_len = s.len() - (DECIMALS - 2);
+    while s.len() > min_len && s.ends_with('0') {
+        s.pop();
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COIN: u64 = 100_000_000;
+
+    fn parse(s: &str) -> Option<u64> {
+        parse_money(s).ok().map(Amount::into_inner)
+    }
+
+    // Derived from `util_ParseMoney` in Bitcoin Core's `util_tests.cpp`.
+    #[test]
+    fn core_parse_money() {
+        assert_eq!(parse("0.0"), Some(0));
+        assert_eq!(parse("."), Some(0));
+        assert_eq!(parse("0."), Some(0));
+        assert_eq!(parse(".0"), Some(0));
+        assert_eq!(parse(".6789"), Some(6789_0000));
+        assert_eq!(parse("12345."), Some(COIN * 12345));
+        assert_eq!(parse("12345.6789"), Some((COIN / 10000) * 123456789));
+        assert_eq!(parse("10000000.00"), Some(COIN * 10000000));
+        assert_eq!(parse("1000000.00"), Some(COIN * 1000000));
+        assert_eq!(parse("100000.00"), Some(COIN * 100000));
+        assert_eq!(parse("10000.00"), Some(COIN * 10000));
+        assert_eq!(parse("1000.00"), Some(COIN * 1000));
+        assert_eq!(parse("100.00"), Some(COIN * 100));
+        assert_eq!(parse("10.00"), Some(COIN * 10));
+        assert_eq!(parse("1.00"), Some(COIN));
+        assert_eq!(parse("1"), Some(COIN));
+        assert_eq!(parse("   1"), Some(COIN));
+        assert_eq!(parse("1   "), Some(COIN));
+        assert_eq!(parse("  1 "), Some(COIN));
+        assert_eq!(parse("0.1"), Some(COIN / 10));
+        assert_eq!(parse("0.01"), Some(COIN / 100));
+        assert_eq!(parse("0.001"), Some(COIN / 1000));
+        assert_eq!(parse("0.0001"), Some(COIN / 10000));
+        assert_eq!(parse("0.00001"), Some(COIN / 100000));
+        assert_eq!(parse("0.000001"), Some(COIN / 1000000));
+        assert_eq!(parse("0.0000001"), Some(COIN / 10000000));
+        assert_eq!(parse("0.00000001"), Some(COIN / 100000000));
+        assert_eq!(parse(" 0.00000001 "), Some(COIN / 100000000));
+        assert_eq!(parse("0.00000001 "), Some(COIN / 100000000));
+        assert_eq!(parse(" 0.00000001"), Some(COIN / 100000000));
+
+        // Amounts that can't be represented.
+        assert_eq!(parse_money("100000000.00"), Err(ParseAmountError::OutOfRange));
+        assert_eq!(parse_money("0.000000001"), Err(ParseAmountError::TooPrecise));
+        assert_eq!(parse_money("1.000000000"), Err(ParseAmountError::TooPrecise));
+        assert_eq!(parse_money("92233720368.54775808"), Err(ParseAmountError::OutOfRange));
+
+        // Empty strings.
+        assert_eq!(parse_money(""), Err(ParseAmountError::Empty));
+        assert_eq!(parse_money(" "), Err(ParseAmountError::Empty));
+        assert_eq!(parse_money("  "), Err(ParseAmountError::Empty));
+
+        // Two numbers.
+        for s in &["..", "0..0", "1 2", " 1 2 ", " 1.2 3 ", " 1 2.3 "] {
+            assert!(parse(s).is_none(), "{:?}", s);
+        }
+        // Embedded whitespace.
+        for s in &[" -1 .2  ", "  1 .2  ", " +1 .2  "] {
+            assert!(parse(s).is_none(), "{:?}", s);
+        }
+
+        assert_eq!(parse_money("-1"), Err(ParseAmountError::InvalidCharacter { pos: 0 }));
+        assert_eq!(parse_money(" 1.5x"), Err(ParseAmountError::InvalidCharacter { pos: 4 }));
+        assert_eq!(parse_money("1e3"), Err(ParseAmountError::InvalidCharacter { pos: 1 }));
+        assert_eq!(parse_money("\0-1"), Err(ParseAmountError::InvalidCharacter { pos: 0 }));
+        assert_eq!(parse_money("1\0"), Err(ParseAmountError::InvalidCharacter { pos: 1 }));
+        assert_eq!(parse_money("21000000"), Ok(Amount::MAX_MONEY));
+        assert_eq!(parse_money("21000000.00000001"), Err(ParseAmountError::OutOfRange));
+    }
+
+    // Derived from `util_FormatMoney` in Bitcoin Core's `util_tests.cpp`.
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn core_format_money() {
+        let format = |sat| format_money(Amount::from_sat(sat));
+        assert_eq!(format(0), "0.00");
+        assert_eq!(format((COIN / 10000) * 123456789), "12345.6789");
+        assert_eq!(format_signed_money(SignedAmount::from_sat(-(COIN as i64))), "-1.00");
+
+        assert_eq!(format(COIN * 10000000), "10000000.00");
+        assert_eq!(format(COIN * 1000000), "1000000.00");
+        assert_eq!(format(COIN * 100000), "100000.00");
+        assert_eq!(format(COIN * 10000), "10000.00");
+        assert_eq!(format(COIN * 1000), "1000.00");
+        assert_eq!(format(COIN * 100), "100.00");
+        assert_eq!(format(COIN * 10), "10.00");
+        assert_eq!(format(COIN), "1.00");
+        assert_eq!(format(COIN / 10), "0.10");
+        assert_eq!(format(COIN / 100), "0.01");
+        assert_eq!(format(COIN / 1000), "0.001");
+        assert_eq!(format(COIN / 10000), "0.0001");
+        assert_eq!(format(COIN / 100000), "0.00001");
+        assert_eq!(format(COIN / 1000000), "0.000001");
+        assert_eq!(format(COIN / 10000000), "0.0000001");
+        assert_eq!(format(COIN / 100000000), "0.00000001");
+
+        #[cfg(not(feature = "strict-money"))]
+        {
+            assert_eq!(format(COIN * 100000000), "100000000.00");
+            let format_signed = |sat| format_signed_money(SignedAmount::from_sat(sat));
+            assert_eq!(format_signed(i64::MAX), "92233720368.54775807");
+            assert_eq!(format_signed(i64::MAX - 1), "92233720368.54775806");
+            assert_eq!(format_signed(i64::MIN + 1), "-92233720368.54775807");
+            assert_eq!(format_signed(i64::MIN), "-92233720368.54775808");
+        }
+
+        // `ValueFromAmount`.
+        assert_eq!(Amount::from_sat(COIN / 10).to_string(), "0.10000000");
+        assert_eq!(SignedAmount::from_sat(-1).to_string(), "-0.00000001");
+    }
+}
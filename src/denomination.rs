@@ -0,0 +1,193 @@
+//! Units in which bitcoin amounts can be expressed.
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::fmt::{self, Display, Formatter};
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::error;
+
+use consts::{MSAT_PER_BTC, MSAT_PER_SAT, SAT_PER_MBTC, SAT_PER_UBTC};
+
+/// A unit in which bitcoin amounts can be expressed.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Denomination {
+    /// BTC, 100,000,000 satoshis.
+    Bitcoin,
+    /// mBTC, 100,000 satoshis.
+    MilliBitcoin,
+    /// uBTC, 100 satoshis.
+    MicroBitcoin,
+    /// bits, 100 satoshis, same as `MicroBitcoin`.
+    Bit,
+    /// satoshi, the smallest on-chain unit.
+    Satoshi,
+    /// msat, a thousandth of a satoshi.
+    MilliSatoshi,
+}
+
+impl Denomination {
+    /// All of the denominations, from the largest to the smallest.
+    pub const ALL: [Denomination; 6] = [
+        Denomination::Bitcoin,
+        Denomination::MilliBitcoin,
+        Denomination::MicroBitcoin,
+        Denomination::Bit,
+        Denomination::Satoshi,
+        Denomination::MilliSatoshi,
+    ];
+
+    /// The amount of millisatoshis in one unit of this denomination.
+    pub fn msat_per_unit(self) -> u64 {
+        match self {
+            Denomination::Bitcoin => MSAT_PER_BTC,
+            Denomination::MilliBitcoin => SAT_PER_MBTC * MSAT_PER_SAT,
+            Denomination::MicroBitcoin | Denomination::Bit => SAT_PER_UBTC * MSAT_PER_SAT,
+            Denomination::Satoshi => MSAT_PER_SAT,
+            Denomination::MilliSatoshi => 1,
+        }
+    }
+
+    /// The number of decimal places a value in this denomination has more
+    /// than the same value in satoshis, e.g. `-8` for BTC and `3` for msat.
+    pub fn precision(self) -> i32 {
+        match self {
+            Denomination::Bitcoin => -8,
+            Denomination::MilliBitcoin => -5,
+            Denomination::MicroBitcoin | Denomination::Bit => -2,
+            Denomination::Satoshi => 0,
+            Denomination::MilliSatoshi => 3,
+        }
+    }
+
+    /// The canonical symbol of this denomination, as used by `Display`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Denomination::Bitcoin => "BTC",
+            Denomination::MilliBitcoin => "mBTC",
+            Denomination::MicroBitcoin => "uBTC",
+            Denomination::Bit => "bits",
+            Denomination::Satoshi => "satoshi",
+            Denomination::MilliSatoshi => "msat",
+        }
+    }
+}
+
+impl Display for Denomination {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.write_str(self.as_str())
+    }
+}
+
+/// Parses a denomination symbol, case-insensitively.
+///
+/// Symbols starting with an uppercase `M` are rejected since they could be
+/// read as mega, e.g. `MBTC`.
+impl FromStr for Denomination {
+    type Err = ParseDenominationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Denomination::from_bytes(s.as_bytes())
+    }
+}
+
+impl Denomination {
+    /// Parses a denomination symbol from its UTF-8 bytes, see `FromStr`.
+    pub(crate) fn from_bytes(s: &[u8]) -> Result<Denomination, ParseDenominationError> {
+        if s.first() == Some(&b'M') {
+            return Err(ParseDenominationError::new(s));
+        }
+
+        SYMBOLS.iter()
+            .find(|&&(symbol, _)| symbol.as_bytes().eq_ignore_ascii_case(s))
+            .map(|&(_, denom)| denom)
+            .ok_or_else(|| ParseDenominationError::new(s))
+    }
+}
+
+/// The symbols accepted by `FromStr`, in lowercase.
+static SYMBOLS: &[(&str, Denomination)] = &[
+    ("btc", Denomination::Bitcoin),
+    ("mbtc", Denomination::MilliBitcoin),
+    ("ubtc", Denomination::MicroBitcoin),
+    ("µbtc", Denomination::MicroBitcoin),
+    ("bit", Denomination::Bit),
+    ("bits", Denomination::Bit),
+    ("sat", Denomination::Satoshi),
+    ("sats", Denomination::Satoshi),
+    ("satoshi", Denomination::Satoshi),
+    ("satoshis", Denomination::Satoshi),
+    ("msat", Denomination::MilliSatoshi),
+    ("msats", Denomination::MilliSatoshi),
+];
+
+/// An error during `Denomination` parsing, the symbol is unknown.
+///
+/// The symbol is only kept, and shown by `Display`, with the `alloc`
+/// feature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDenominationError(#[cfg(feature = "alloc")] String);
+
+impl ParseDenominationError {
+    #[cfg(feature = "alloc")]
+    fn new(symbol: &[u8]) -> ParseDenominationError {
+        ParseDenominationError(String::from_utf8_lossy(symbol).into_owned())
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    fn new(_: &[u8]) -> ParseDenominationError {
+        ParseDenominationError()
+    }
+}
+
+impl Display for ParseDenominationError {
+    #[cfg(feature = "alloc")]
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(fmt, "unknown denomination: {}", self.0)
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.write_str("unknown denomination")
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for ParseDenominationError {
+    fn description(&self) -> &'static str {
+        "unknown denomination"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn denomination_round_trip() {
+        for &denom in Denomination::ALL.iter() {
+            assert_eq!(Denomination::from_str(&denom.to_string()), Ok(denom));
+        }
+    }
+
+    #[test]
+    fn denomination_from_str() {
+        assert_eq!(Denomination::from_str("btc"), Ok(Denomination::Bitcoin));
+        assert_eq!(Denomination::from_str("mbtc"), Ok(Denomination::MilliBitcoin));
+        assert_eq!(Denomination::from_str("µBTC"), Ok(Denomination::MicroBitcoin));
+        assert_eq!(Denomination::from_str("bit"), Ok(Denomination::Bit));
+        assert_eq!(Denomination::from_str("SAT"), Ok(Denomination::Satoshi));
+        assert_eq!(Denomination::from_str("msat"), Ok(Denomination::MilliSatoshi));
+        assert!(Denomination::from_str("MBTC").is_err());
+        assert!(Denomination::from_str("Msat").is_err());
+        assert!(Denomination::from_str("eth").is_err());
+        assert!(Denomination::from_str("").is_err());
+    }
+
+    #[test]
+    fn denomination_factors() {
+        for &denom in Denomination::ALL.iter() {
+            assert_eq!(denom.msat_per_unit(), 10u64.pow((3 - denom.precision()) as u32));
+        }
+    }
+}
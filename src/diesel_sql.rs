@@ -0,0 +1,113 @@
+//! Diesel mapping of amounts to `BigInt` columns of satoshis.
+//!
+//! Reading works with any backend, writing with the backends enabled by the
+//! `diesel-postgres`, `diesel-mysql` and `diesel-sqlite` features.
+//! `Amount`s above `i64::MAX` satoshis can't be written, and negative values
+//! can't be read as `Amount`s.
+
+use std::convert::TryFrom;
+
+use diesel::backend::Backend;
+use diesel::deserialize::{self, FromSql};
+#[cfg(feature = "diesel-mysql")]
+use diesel::mysql::Mysql;
+#[cfg(feature = "diesel-postgres")]
+use diesel::pg::Pg;
+#[cfg(feature = "diesel-sqlite")]
+use diesel::serialize::IsNull;
+#[cfg(any(feature = "diesel-postgres", feature = "diesel-mysql", feature = "diesel-sqlite"))]
+use diesel::serialize::{self, Output, ToSql};
+use diesel::sql_types::BigInt;
+#[cfg(feature = "diesel-sqlite")]
+use diesel::sqlite::Sqlite;
+
+use {Amount, SignedAmount};
+
+/// Reads a number of satoshis, negative values are rejected.
+impl<DB: Backend> FromSql<BigInt, DB> for Amount
+where
+    i64: FromSql<BigInt, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Amount> {
+        let sat = i64::from_sql(bytes)?;
+        Ok(Amount::try_from(sat)?)
+    }
+}
+
+impl<DB: Backend> FromSql<BigInt, DB> for SignedAmount
+where
+    i64: FromSql<BigInt, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<SignedAmount> {
+        let sat = i64::from_sql(bytes)?;
+        Ok(SignedAmount::try_from_sat(sat)?)
+    }
+}
+
+/// Writes the satoshis of amounts through the `i64` implementation of
+/// backends that serialize bound values right away.
+#[cfg(any(feature = "diesel-postgres", feature = "diesel-mysql"))]
+macro_rules! impl_to_sql_bytes {
+    ($backend:ty) => {
+        /// Writes a number of satoshis, amounts above `i64::MAX` satoshis
+        /// fail.
+        impl ToSql<BigInt, $backend> for Amount {
+            fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, $backend>) -> serialize::Result {
+                let sat = i64::try_from(*self)?;
+                ToSql::<BigInt, $backend>::to_sql(&sat, &mut out.reborrow())
+            }
+        }
+
+        impl ToSql<BigInt, $backend> for SignedAmount {
+            fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, $backend>) -> serialize::Result {
+                ToSql::<BigInt, $backend>::to_sql(&self.into_inner(), &mut out.reborrow())
+            }
+        }
+    };
+}
+
+#[cfg(feature = "diesel-postgres")]
+impl_to_sql_bytes!(Pg);
+#[cfg(feature = "diesel-mysql")]
+impl_to_sql_bytes!(Mysql);
+
+/// Binds a number of satoshis, amounts above `i64::MAX` satoshis fail.
+#[cfg(feature = "diesel-sqlite")]
+impl ToSql<BigInt, Sqlite> for Amount {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+        out.set_value(i64::try_from(*self)?);
+        Ok(IsNull::No)
+    }
+}
+
+#[cfg(feature = "diesel-sqlite")]
+impl ToSql<BigInt, Sqlite> for SignedAmount {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+        out.set_value(self.into_inner());
+        Ok(IsNull::No)
+    }
+}
+
+#[cfg(all(test, feature = "diesel-sqlite"))]
+mod tests {
+    use diesel::dsl::sql;
+    use diesel::sql_types::BigInt;
+    use diesel::{select, Connection, IntoSql, RunQueryDsl, SqliteConnection};
+
+    use {Amount, SignedAmount};
+
+    #[test]
+    fn diesel_sqlite_bigint() {
+        let mut conn = SqliteConnection::establish(":memory:").unwrap();
+
+        let amount = select(Amount::from_sat(253583).into_sql::<BigInt>()).get_result(&mut conn);
+        assert_eq!(amount, Ok(Amount::from_sat(253583)));
+        let amount = select(SignedAmount::from_sat(-1).into_sql::<BigInt>()).get_result(&mut conn);
+        assert_eq!(amount, Ok(SignedAmount::from_sat(-1)));
+
+        assert!(select(sql::<BigInt>("-1")).get_result::<Amount>(&mut conn).is_err());
+        assert!(select(Amount::max_value().into_sql::<BigInt>()).get_result::<Amount>(&mut conn).is_err());
+        #[cfg(feature = "strict-money")]
+        assert!(select(sql::<BigInt>("9223372036854775807")).get_result::<SignedAmount>(&mut conn).is_err());
+    }
+}
@@ -0,0 +1,679 @@
+//! Formatting of amounts.
+
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+use core::fmt::{self, Debug, Display, Formatter, Write};
+use core::str;
+
+#[cfg(feature = "locale")]
+use locale::Locale;
+use {Amount, Denomination, SignedAmount};
+
+/// A formattable view of an amount in a given denomination, see
+/// `Amount::display_in`.
+///
+/// By default the amount is formatted with as many decimal places as needed
+/// to represent a satoshi in the denomination, e.g. 8 for BTC, and without
+/// the denomination suffix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayAmount {
+    negative: bool,
+    sat: u64,
+    denom: Denomination,
+    show_denomination: bool,
+    symbol: &'static str,
+    precision: Option<usize>,
+    grouping: Grouping,
+    group_fraction: bool,
+    trim_trailing_zeros: bool,
+    accounting: bool,
+    decimal_separator: Option<char>,
+    #[cfg(feature = "locale")]
+    locale: Option<Locale>,
+}
+
+/// How the digits of the integer part are grouped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Grouping {
+    None,
+    /// With the group separator of the locale, `,` without one.
+    Locale,
+    Separator(char),
+}
+
+impl DisplayAmount {
+    fn new(negative: bool, sat: u64, denom: Denomination) -> DisplayAmount {
+        DisplayAmount {
+            negative,
+            sat,
+            denom,
+            show_denomination: false,
+            symbol: denom.as_str(),
+            precision: None,
+            grouping: Grouping::None,
+            group_fraction: false,
+            trim_trailing_zeros: false,
+            accounting: false,
+            decimal_separator: None,
+            #[cfg(feature = "locale")]
+            locale: None,
+        }
+    }
+
+    /// Picks the denomination for `display_human`, with the denomination
+    /// symbol, the short `sat` for satoshis, digit grouping and no trailing
+    /// zeros.
+    fn human(negative: bool, sat: u64) -> DisplayAmount {
+        let denom = if sat < 100_000 {
+            Denomination::Satoshi
+        } else if sat < 10_000_000 {
+            Denomination::MilliBitcoin
+        } else {
+            Denomination::Bitcoin
+        };
+
+        let mut display = DisplayAmount::new(negative, sat, denom)
+            .show_denomination(true)
+            .trim_trailing_zeros(true);
+        display.grouping = Grouping::Locale;
+        if denom == Denomination::Satoshi {
+            display.symbol = "sat";
+        }
+        display
+    }
+
+    /// Sets whether the denomination symbol is appended, e.g. `0.1 BTC`.
+    pub fn show_denomination(mut self, show: bool) -> DisplayAmount {
+        self.show_denomination = show;
+        self
+    }
+
+    /// Groups the digits of the integer part in threes using `separator`,
+    /// e.g. `1,234,567` with `','`.
+    pub fn group_digits(mut self, separator: char) -> DisplayAmount {
+        self.grouping = Grouping::Separator(separator);
+        self
+    }
+
+    /// Sets the character separating the integer part from the decimal
+    /// places, `'.'` by default.
+    pub fn decimal_separator(mut self, separator: char) -> DisplayAmount {
+        self.decimal_separator = Some(separator);
+        self
+    }
+
+    /// Uses the separators of `locale`, its digit group separator if the
+    /// digits are grouped. The separators set with `decimal_separator` and
+    /// `group_digits` take precedence, whatever the order of the calls.
+    #[cfg(feature = "locale")]
+    pub fn locale(mut self, locale: Locale) -> DisplayAmount {
+        self.locale = Some(locale);
+        self
+    }
+
+    /// Groups the digits of the integer part in threes using the group
+    /// separator of the locale set with `locale`, `,` if none is.
+    #[cfg(feature = "locale")]
+    pub fn group_digits_locale(mut self) -> DisplayAmount {
+        self.grouping = Grouping::Locale;
+        self
+    }
+
+    /// Sets whether the decimal places are also grouped in threes when
+    /// `group_digits` is used, e.g. `12 345.678 90` with `' '`.
+    pub fn group_fraction(mut self, group: bool) -> DisplayAmount {
+        self.group_fraction = group;
+        self
+    }
+
+    /// Sets whether the trailing zeros of the decimal places are removed,
+    /// e.g. `0.1` instead of `0.10000000`. The decimal point is removed too
+    /// for whole values.
+    pub fn trim_trailing_zeros(mut self, trim: bool) -> DisplayAmount {
+        self.trim_trailing_zeros = trim;
+        self
+    }
+
+    /// Sets whether negative amounts are formatted accounting style, inside
+    /// parentheses instead of with a minus sign, e.g. `(0.00100000)`. Other
+    /// amounts are surrounded by spaces so they line up in columns.
+    pub fn accounting(mut self, accounting: bool) -> DisplayAmount {
+        self.accounting = accounting;
+        self
+    }
+
+    /// Sets the exact number of decimal places, rounding to the nearest
+    /// value (half-way cases away from zero) if it's less than needed.
+    pub fn precision(mut self, precision: usize) -> DisplayAmount {
+        self.precision = Some(precision);
+        self
+    }
+}
+
+/// Honors the width, fill, alignment (right by default), precision, `+` and
+/// `0` flags of the formatter, the precision taking priority over the one
+/// set with `DisplayAmount::precision`.
+impl Display for DisplayAmount {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        let unit = u128::from(self.denom.msat_per_unit());
+        let unit_digits = (3 - self.denom.precision()) as usize;
+        let natural = (-self.denom.precision()).max(0) as usize;
+        let mut precision = fmt.precision().or(self.precision).unwrap_or(natural);
+
+        // Only the decimal places that can be non-zero are computed, the
+        // rest are padded.
+        let mut computed = precision.min(unit_digits);
+        let mut scale = 10u128.pow(computed as u32);
+        let msat = u128::from(self.sat) * 1_000 * scale;
+        let mut scaled = msat / unit;
+        if (msat % unit) * 2 >= unit {
+            scaled += 1;
+        }
+
+        if self.trim_trailing_zeros {
+            while computed > 0 && scaled.is_multiple_of(10) {
+                scaled /= 10;
+                scale /= 10;
+                computed -= 1;
+            }
+            precision = computed;
+        }
+
+        let negative = self.negative && scaled != 0;
+        let (sign, close) = if self.accounting {
+            if negative { ("(", ")") } else { (" ", " ") }
+        } else if negative {
+            ("-", "")
+        } else if fmt.sign_plus() {
+            ("+", "")
+        } else {
+            ("", "")
+        };
+        let (int, frac) = (scaled / scale, scaled % scale);
+
+        let int_digits = digits(int);
+        let mut len = sign.len() + int_digits + close.len();
+        if precision > 0 {
+            len += 1 + precision;
+        }
+        let (decimal_separator, separator) = self.separators();
+        if separator.is_some() {
+            len += (int_digits - 1) / 3;
+            if self.group_fraction && precision > 0 {
+                len += (precision - 1) / 3;
+            }
+        }
+        if self.show_denomination {
+            len += 1 + self.symbol.chars().count();
+        }
+
+        let padding = fmt.width().map_or(0, |width| width.saturating_sub(len));
+        if fmt.sign_aware_zero_pad() {
+            fmt.write_str(sign)?;
+            write_repeated(fmt, '0', padding)?;
+        } else {
+            let (pre, post) = match fmt.align() {
+                Some(fmt::Alignment::Left) => (0, padding),
+                Some(fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+                Some(fmt::Alignment::Right) | None => (padding, 0),
+            };
+            let fill = fmt.fill();
+            write_repeated(fmt, fill, pre)?;
+            fmt.write_str(sign)?;
+            self.write_number(fmt, (decimal_separator, separator), int, frac, computed, precision)?;
+            fmt.write_str(close)?;
+            return write_repeated(fmt, fill, post);
+        }
+
+        self.write_number(fmt, (decimal_separator, separator), int, frac, computed, precision)?;
+        fmt.write_str(close)
+    }
+}
+
+impl DisplayAmount {
+    /// Writes this amount to `w`, e.g. a fixed-size buffer, without
+    /// allocating.
+    pub fn write_to<W: Write>(&self, w: &mut W) -> fmt::Result {
+        write!(w, "{}", self)
+    }
+
+    /// The decimal and digit group separators, the latter only if the
+    /// digits are grouped, resolved against the locale.
+    fn separators(&self) -> (char, Option<char>) {
+        #[cfg(feature = "locale")]
+        let (decimal, group) = self.locale.map_or(('.', ','), |locale| {
+            (locale.decimal_separator(), locale.group_separator())
+        });
+        #[cfg(not(feature = "locale"))]
+        let (decimal, group) = ('.', ',');
+
+        let separator = match self.grouping {
+            Grouping::None => None,
+            Grouping::Locale => Some(group),
+            Grouping::Separator(separator) => Some(separator),
+        };
+        (self.decimal_separator.unwrap_or(decimal), separator)
+    }
+
+    fn write_number(&self, fmt: &mut Formatter, (decimal_separator, separator): (char, Option<char>),
+                    int: u128, frac: u128, computed: usize, precision: usize) -> fmt::Result {
+        let mut out = Buffer::new(fmt);
+
+        let mut int_digits = [0; 40];
+        let int_len = decimal_digits(int, &mut int_digits);
+        for (i, &digit) in int_digits[int_digits.len() - int_len..].iter().enumerate() {
+            let pos = int_len - i;
+            if i > 0 && pos.is_multiple_of(3) {
+                if let Some(separator) = separator {
+                    out.push(separator)?;
+                }
+            }
+            out.push(char::from(digit))?;
+        }
+
+        if precision > 0 {
+            // Only up to 11 decimal places, of msat in BTC, are computed.
+            let mut frac_digits = [b'0'; 40];
+            decimal_digits(frac, &mut frac_digits);
+            let frac_digits = &frac_digits[frac_digits.len() - computed..];
+
+            out.push(decimal_separator)?;
+            for i in 0..precision {
+                if i > 0 && i.is_multiple_of(3) && self.group_fraction {
+                    if let Some(separator) = separator {
+                        out.push(separator)?;
+                    }
+                }
+                out.push(frac_digits.get(i).map_or('0', |&digit| char::from(digit)))?;
+            }
+        }
+
+        if self.show_denomination {
+            out.push(' ')?;
+            for c in self.symbol.chars() {
+                out.push(c)?;
+            }
+        }
+
+        out.flush()
+    }
+}
+
+/// Writes the decimal digits of `n`, as ASCII, to the end of `digits`,
+/// returns how many there are.
+fn decimal_digits(mut n: u128, digits: &mut [u8; 40]) -> usize {
+    let mut len = 0;
+    loop {
+        len += 1;
+        digits[digits.len() - len] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            return len;
+        }
+    }
+}
+
+/// The number of decimal digits of `n`.
+fn digits(mut n: u128) -> usize {
+    let mut digits = 1;
+    while n >= 10 {
+        n /= 10;
+        digits += 1;
+    }
+    digits
+}
+
+/// Buffers characters on the stack, so a number is written to the formatter
+/// in a single call rather than once per character.
+struct Buffer<'a, 'b: 'a> {
+    fmt: &'a mut Formatter<'b>,
+    bytes: [u8; 64],
+    len: usize,
+}
+
+impl<'a, 'b> Buffer<'a, 'b> {
+    fn new(fmt: &'a mut Formatter<'b>) -> Buffer<'a, 'b> {
+        Buffer { fmt, bytes: [0; 64], len: 0 }
+    }
+
+    fn push(&mut self, c: char) -> fmt::Result {
+        if self.len + c.len_utf8() > self.bytes.len() {
+            self.flush()?;
+        }
+        self.len += c.encode_utf8(&mut self.bytes[self.len..]).len();
+        Ok(())
+    }
+
+    fn flush(&mut self) -> fmt::Result {
+        let s = str::from_utf8(&self.bytes[..self.len]).expect("only whole chars are buffered");
+        self.len = 0;
+        self.fmt.write_str(s)
+    }
+}
+
+fn write_repeated(fmt: &mut Formatter, c: char, count: usize) -> fmt::Result {
+    for _ in 0..count {
+        fmt.write_char(c)?;
+    }
+    Ok(())
+}
+
+impl Amount {
+    /// Returns a formattable view of this amount in the given denomination.
+    pub fn display_in(self, denom: Denomination) -> DisplayAmount {
+        DisplayAmount::new(false, self.into_inner(), denom)
+    }
+
+    /// Returns a formattable view of this amount in the most readable
+    /// denomination for its magnitude: satoshis below 1 mBTC, mBTC below
+    /// 0.1 BTC and BTC otherwise, e.g. `2,100 sat`, `1.5 mBTC` or
+    /// `0.25 BTC`.
+    pub fn display_human(self) -> DisplayAmount {
+        DisplayAmount::human(false, self.into_inner())
+    }
+
+    /// Formats this amount in its canonical form, a decimal number of BTC
+    /// without trailing zeros, e.g. `0.1` or `21000000`.
+    ///
+    /// Every amount has exactly one canonical form and
+    /// `Amount::from_str(&amt.to_canonical_string()) == Ok(amt)` holds for
+    /// all of them, so it's fit for storage and comparison as a string.
+    #[cfg(feature = "alloc")]
+    pub fn to_canonical_string(self) -> String {
+        self.display_in(Denomination::Bitcoin).trim_trailing_zeros(true).to_string()
+    }
+}
+
+impl SignedAmount {
+    /// Returns a formattable view of this amount in the given denomination.
+    pub fn display_in(self, denom: Denomination) -> DisplayAmount {
+        DisplayAmount::new(self.is_negative(), self.unsigned_abs().into_inner(), denom)
+    }
+
+    /// Returns a formattable view of this amount in the most readable
+    /// denomination for its magnitude: satoshis below 1 mBTC, mBTC below
+    /// 0.1 BTC and BTC otherwise, e.g. `2,100 sat`, `1.5 mBTC` or
+    /// `0.25 BTC`.
+    pub fn display_human(self) -> DisplayAmount {
+        DisplayAmount::human(self.is_negative(), self.unsigned_abs().into_inner())
+    }
+
+    /// Formats this amount in its canonical form, a decimal number of BTC
+    /// without trailing zeros, e.g. `-0.1` or `21000000`.
+    ///
+    /// Every amount has exactly one canonical form and
+    /// `SignedAmount::from_str(&amt.to_canonical_string()) == Ok(amt)` holds
+    /// for all of them.
+    #[cfg(feature = "alloc")]
+    pub fn to_canonical_string(self) -> String {
+        self.display_in(Denomination::Bitcoin).trim_trailing_zeros(true).to_string()
+    }
+}
+
+/// Formats the amount as a decimal BTC string with 8 decimal places, which
+/// parses back to the same amount.
+impl Display for Amount {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        Display::fmt(&self.display_in(Denomination::Bitcoin), fmt)
+    }
+}
+
+/// Formats the amount as a decimal BTC string with 8 decimal places, which
+/// parses back to the same amount.
+impl Display for SignedAmount {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        Display::fmt(&self.display_in(Denomination::Bitcoin), fmt)
+    }
+}
+
+/// Formats the amount both in satoshis and in BTC, e.g.
+/// `Amount(253583 sat = 0.00253583 BTC)`.
+impl Debug for Amount {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(fmt, "Amount({} sat = {} BTC)", self.into_inner(), self)
+    }
+}
+
+/// Formats the amount both in satoshis and in BTC, e.g.
+/// `SignedAmount(-253583 sat = -0.00253583 BTC)`.
+impl Debug for SignedAmount {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(fmt, "SignedAmount({} sat = {} BTC)", self.into_inner(), self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn amount_display() {
+        assert_eq!(Amount::from_sat(253583).to_string(), "0.00253583");
+        assert_eq!(Amount::from_sat(10_000_000).to_string(), "0.10000000");
+        assert_eq!(Amount::zero().to_string(), "0.00000000");
+        assert_eq!(Amount::MAX_MONEY.to_string(), "21000000.00000000");
+        assert_eq!(Amount::max_value().to_string(), "184467440737.09551615");
+    }
+
+    #[test]
+    fn signed_amount_display() {
+        assert_eq!(SignedAmount::from_sat(-253583).to_string(), "-0.00253583");
+        assert_eq!(SignedAmount::from_sat(150_000_000).to_string(), "1.50000000");
+        assert_eq!(SignedAmount::min_value().to_string(), "-92233720368.54775808");
+    }
+
+    #[test]
+    fn amount_debug() {
+        assert_eq!(format!("{:?}", Amount::from_sat(253583)), "Amount(253583 sat = 0.00253583 BTC)");
+        assert_eq!(format!("{:?}", SignedAmount::from_sat(-253583)),
+                   "SignedAmount(-253583 sat = -0.00253583 BTC)");
+        assert_eq!(format!("{:?}", Some(Amount::from_sat(1))), "Some(Amount(1 sat = 0.00000001 BTC))");
+    }
+
+    #[test]
+    fn display_round_trip() {
+        for &sat in &[0, 1, 253583, 29_999_999, 10_000_000, 2_100_000_000_000_000] {
+            let amt = Amount::from_sat(sat);
+            assert_eq!(Amount::from_str(&amt.to_string()).unwrap(), amt);
+            let amt = -SignedAmount::from_sat(sat as i64);
+            assert_eq!(SignedAmount::from_str(&amt.to_string()).unwrap(), amt);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn display_canonical() {
+        assert_eq!(Amount::from_sat(0).to_canonical_string(), "0");
+        assert_eq!(Amount::from_sat(10_000_000).to_canonical_string(), "0.1");
+        assert_eq!(Amount::MAX_MONEY.to_canonical_string(), "21000000");
+        assert_eq!(SignedAmount::from_sat(-253583).to_canonical_string(), "-0.00253583");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn display_canonical_round_trip() {
+        use proptest_crate::test_runner::TestRunner;
+
+        // Only valid money can be created with the `strict-money` feature.
+        let max = Amount::MAX_MONEY.into_inner();
+        let (sats, signed_sats) = if cfg!(feature = "strict-money") {
+            (0..=max, -(max as i64)..=max as i64)
+        } else {
+            (0..=u64::MAX, i64::MIN..=i64::MAX)
+        };
+        let mut runner = TestRunner::default();
+        runner.run(&sats, |sat| {
+            let amt = Amount::from_sat(sat);
+            assert_eq!(Amount::from_str(&amt.to_canonical_string()), Ok(amt));
+            Ok(())
+        }).unwrap();
+        runner.run(&signed_sats, |sat| {
+            let amt = SignedAmount::from_sat(sat);
+            assert_eq!(SignedAmount::from_str(&amt.to_canonical_string()), Ok(amt));
+            Ok(())
+        }).unwrap();
+    }
+
+    #[test]
+    fn display_in() {
+        let amt = Amount::from_sat(253583);
+        assert_eq!(amt.display_in(Denomination::Bitcoin).to_string(), "0.00253583");
+        assert_eq!(amt.display_in(Denomination::MilliBitcoin).to_string(), "2.53583");
+        assert_eq!(amt.display_in(Denomination::MicroBitcoin).to_string(), "2535.83");
+        assert_eq!(amt.display_in(Denomination::Bit).to_string(), "2535.83");
+        assert_eq!(amt.display_in(Denomination::Satoshi).to_string(), "253583");
+        assert_eq!(amt.display_in(Denomination::MilliSatoshi).to_string(), "253583000");
+
+        let amt = SignedAmount::from_sat(-253583);
+        assert_eq!(amt.display_in(Denomination::MilliBitcoin).to_string(), "-2.53583");
+    }
+
+    #[test]
+    fn display_in_options() {
+        let amt = Amount::from_sat(253583);
+        assert_eq!(amt.display_in(Denomination::Bitcoin).show_denomination(true).to_string(),
+                   "0.00253583 BTC");
+        assert_eq!(amt.display_in(Denomination::Bit).show_denomination(true).to_string(),
+                   "2535.83 bits");
+        assert_eq!(amt.display_in(Denomination::Bitcoin).precision(4).to_string(), "0.0025");
+        assert_eq!(amt.display_in(Denomination::MilliBitcoin).precision(2).to_string(), "2.54");
+        assert_eq!(amt.display_in(Denomination::MilliBitcoin).precision(0).to_string(), "3");
+        assert_eq!(amt.display_in(Denomination::Satoshi).precision(2).to_string(), "253583.00");
+        assert_eq!(amt.display_in(Denomination::Bitcoin).precision(12).to_string(),
+                   "0.002535830000");
+
+        let amt = SignedAmount::from_sat(-4);
+        assert_eq!(amt.display_in(Denomination::Bitcoin).precision(2).to_string(), "0.00");
+        assert_eq!(amt.display_in(Denomination::Satoshi).precision(1).show_denomination(true)
+                      .to_string(), "-4.0 satoshi");
+    }
+
+    #[test]
+    fn display_formatter_flags() {
+        let amt = Amount::from_sat(253583);
+        assert_eq!(format!("{:>15}", amt), "     0.00253583");
+        assert_eq!(format!("{:15}", amt), "     0.00253583");
+        assert_eq!(format!("{:<15}|", amt), "0.00253583     |");
+        assert_eq!(format!("{:^14}", amt), "  0.00253583  ");
+        assert_eq!(format!("{:*>12}", amt), "**0.00253583");
+        assert_eq!(format!("{:.4}", amt), "0.0025");
+        assert_eq!(format!("{:>15.8}", amt), "     0.00253583");
+        assert_eq!(format!("{:+}", amt), "+0.00253583");
+        assert_eq!(format!("{:+013}", amt), "+000.00253583");
+        assert_eq!(format!("{:5}", amt), "0.00253583");
+
+        let amt = SignedAmount::from_sat(-253583);
+        assert_eq!(format!("{:>12}", amt), " -0.00253583");
+        assert_eq!(format!("{:012}", amt), "-00.00253583");
+        assert_eq!(format!("{:+}", amt), "-0.00253583");
+
+        let amt = Amount::from_sat(253583).display_in(Denomination::Bit).show_denomination(true);
+        assert_eq!(format!("{:>14}", amt), "  2535.83 bits");
+        assert_eq!(format!("{:.0}", amt), "2536 bits");
+    }
+
+    #[test]
+    fn display_digit_grouping() {
+        let amt = Amount::from_sat(1_234_567);
+        assert_eq!(amt.display_in(Denomination::Satoshi).group_digits(',')
+                      .show_denomination(true).to_string(), "1,234,567 satoshi");
+        assert_eq!(Amount::from_sat(123).display_in(Denomination::Satoshi).group_digits(',')
+                      .to_string(), "123");
+        assert_eq!(Amount::from_sat(1234).display_in(Denomination::Satoshi).group_digits(',')
+                      .to_string(), "1,234");
+
+        let amt = Amount::from_sat(1_234_567_890_000);
+        assert_eq!(amt.display_in(Denomination::Bitcoin).group_digits(' ').group_fraction(true)
+                      .show_denomination(true).to_string(), "12 345.678 900 00 BTC");
+        assert_eq!(amt.display_in(Denomination::Bitcoin).group_digits(',').to_string(),
+                   "12,345.67890000");
+
+        let amt = SignedAmount::from_sat(-123_456_789_000);
+        assert_eq!(format!("{:>12}", amt.display_in(Denomination::Bitcoin).group_digits(',')
+                                         .precision(2)), "   -1,234.57");
+    }
+
+    #[test]
+    fn display_trim_trailing_zeros() {
+        let display = |sat, denom| Amount::from_sat(sat).display_in(denom).trim_trailing_zeros(true);
+        assert_eq!(display(10_000_000, Denomination::Bitcoin).to_string(), "0.1");
+        assert_eq!(display(253583, Denomination::Bitcoin).to_string(), "0.00253583");
+        assert_eq!(display(100_000_000, Denomination::Bitcoin).to_string(), "1");
+        assert_eq!(display(0, Denomination::Bitcoin).to_string(), "0");
+        assert_eq!(display(150_000, Denomination::MilliBitcoin).show_denomination(true)
+                      .to_string(), "1.5 mBTC");
+        assert_eq!(display(1_000, Denomination::Satoshi).precision(3).to_string(), "1000");
+        assert_eq!(format!("{:.3}", display(123_456_789, Denomination::Bitcoin)), "1.235");
+        assert_eq!(format!("{:.2}", display(100_400_000, Denomination::Bitcoin)), "1");
+        assert_eq!(format!("{:>6}", display(10_000_000, Denomination::Bitcoin)), "   0.1");
+    }
+
+    #[test]
+    fn display_human() {
+        assert_eq!(Amount::from_sat(2_100).display_human().to_string(), "2,100 sat");
+        assert_eq!(Amount::from_sat(99_999).display_human().to_string(), "99,999 sat");
+        assert_eq!(Amount::from_sat(100_000).display_human().to_string(), "1 mBTC");
+        assert_eq!(Amount::from_sat(150_000).display_human().to_string(), "1.5 mBTC");
+        assert_eq!(Amount::from_sat(25_000_000).display_human().to_string(), "0.25 BTC");
+        assert_eq!(Amount::MAX_MONEY.display_human().to_string(), "21,000,000 BTC");
+        assert_eq!(Amount::zero().display_human().to_string(), "0 sat");
+        assert_eq!(SignedAmount::from_sat(-150_000).display_human().to_string(), "-1.5 mBTC");
+    }
+
+    #[test]
+    fn display_accounting() {
+        let debit = SignedAmount::from_sat(-100_000).display_in(Denomination::Bitcoin);
+        let credit = SignedAmount::from_sat(2_500_000).display_in(Denomination::Bitcoin);
+        assert_eq!(debit.accounting(true).to_string(), "(0.00100000)");
+        assert_eq!(credit.accounting(true).to_string(), " 0.02500000 ");
+        assert_eq!(format!("{:>14}", debit.accounting(true)), "  (0.00100000)");
+        assert_eq!(format!("{:>14}", credit.accounting(true)), "   0.02500000 ");
+        assert_eq!(debit.accounting(true).show_denomination(true).to_string(),
+                   "(0.00100000 BTC)");
+        assert_eq!(debit.to_string(), "-0.00100000");
+        assert_eq!(Amount::from_sat(1).display_in(Denomination::Satoshi).accounting(true)
+                      .to_string(), " 1 ");
+    }
+
+    #[test]
+    fn display_write_to() {
+        /// A fixed-size writer, to show nothing is allocated.
+        struct Fixed {
+            bytes: [u8; 32],
+            len: usize,
+        }
+
+        impl Write for Fixed {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                let end = self.len + s.len();
+                if end > self.bytes.len() {
+                    return Err(fmt::Error);
+                }
+                self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+                self.len = end;
+                Ok(())
+            }
+        }
+
+        let mut w = Fixed { bytes: [0; 32], len: 0 };
+        let display = Amount::from_sat(123_456_789_000).display_in(Denomination::Bitcoin)
+            .group_digits('\u{202f}').show_denomination(true);
+        display.write_to(&mut w).unwrap();
+        assert_eq!(str::from_utf8(&w.bytes[..w.len]).unwrap(), "1\u{202f}234.56789000 BTC");
+
+        let display = SignedAmount::min_value().display_in(Denomination::MilliSatoshi)
+            .group_digits(',').group_fraction(true).precision(20);
+        let mut w = Fixed { bytes: [0; 32], len: 0 };
+        assert_eq!(display.write_to(&mut w), Err(fmt::Error));
+        assert_eq!(display.to_string(),
+                   "-9,223,372,036,854,775,808,000.000,000,000,000,000,000,00");
+    }
+
+    #[test]
+    fn display_decimal_separator() {
+        let amt = Amount::from_sat(1_234_567_890_000).display_in(Denomination::Bitcoin);
+        assert_eq!(amt.decimal_separator(',').group_digits('.').to_string(), "12.345,67890000");
+    }
+}
@@ -0,0 +1,277 @@
+//! Consensus encoding of amounts, the 8-byte little-endian value field of
+//! transaction outputs, and a versioned storage encoding.
+//!
+//! # Storage encoding
+//!
+//! `encode_to_vec` and `decode_from_slice` use a stable format meant for
+//! amounts persisted e.g. in key-value stores, which every future version
+//! of this crate will keep decoding, whatever the representation of the
+//! amount types becomes. Version 1, the current one, is 9 bytes long:
+//!
+//! | Offset | Length | Content                                               |
+//! |--------|--------|-------------------------------------------------------|
+//! | 0      | 1      | The format version, `0x01`.                           |
+//! | 1      | 8      | The satoshis, big-endian, two's complement if signed. |
+//!
+//! Big-endian satoshis make the encodings of `Amount`s sort by value. The
+//! encodings of `Amount` and `SignedAmount` only agree below `i64::MAX`
+//! satoshis and above zero, so a value must be decoded as the type it was
+//! encoded from.
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::fmt::Write;
+use core::fmt::{self, Display, Formatter};
+#[cfg(feature = "std")]
+use std::error;
+
+use {Amount, OutOfRangeError, SignedAmount};
+
+macro_rules! impl_encode {
+    ($ty:ident, $inner:ty) => {
+        impl $ty {
+            /// Encodes this amount as the 8 little-endian bytes of the value
+            /// field of a transaction output.
+            pub fn to_le_bytes(self) -> [u8; 8] {
+                self.into_inner().to_le_bytes()
+            }
+
+            /// Decodes an amount from the 8 little-endian bytes of the value
+            /// field of a transaction output.
+            ///
+            /// With the `strict-money` feature enabled, values out of the
+            /// valid money range are an error.
+            pub fn from_le_bytes(bytes: [u8; 8]) -> Result<$ty, OutOfRangeError> {
+                $ty::try_from_sat(<$inner>::from_le_bytes(bytes))
+            }
+
+            /// Encodes this amount as the lowercase hex of its little-endian
+            /// bytes, e.g. `8fde030000000000` for 253583 satoshis.
+            #[cfg(feature = "alloc")]
+            pub fn to_hex(self) -> String {
+                let mut hex = String::with_capacity(16);
+                for byte in &self.to_le_bytes() {
+                    write!(hex, "{:02x}", byte).expect("writing to a String can't fail");
+                }
+                hex
+            }
+
+            /// Decodes an amount from the hex of its little-endian bytes, in
+            /// lowercase or uppercase.
+            ///
+            /// With the `strict-money` feature enabled, values out of the
+            /// valid money range are an error.
+            pub fn from_hex(hex: &str) -> Result<$ty, ParseHexError> {
+                $ty::from_le_bytes(decode_hex(hex)?).map_err(|_| ParseHexError::OutOfRange)
+            }
+
+            /// Encodes this amount in the latest version of a stable storage
+            /// encoding, which every future version of this crate will keep
+            /// decoding.
+            ///
+            /// Version 1, the current one, is the version byte `0x01`
+            /// followed by the 8 big-endian bytes of satoshis, two's
+            /// complement for `SignedAmount`. Encoded `Amount`s sort by
+            /// value.
+            #[cfg(feature = "alloc")]
+            pub fn encode_to_vec(self) -> Vec<u8> {
+                let mut bytes = Vec::with_capacity(ENCODED_LEN);
+                bytes.push(ENCODING_VERSION);
+                bytes.extend(&self.into_inner().to_be_bytes());
+                bytes
+            }
+
+            /// Decodes an amount in any version of the storage encoding of
+            /// `encode_to_vec` from the start of `bytes`, returning it with
+            /// the number of bytes read.
+            ///
+            /// With the `strict-money` feature enabled, values out of the
+            /// valid money range are an error.
+            pub fn decode_from_slice(bytes: &[u8]) -> Result<($ty, usize), DecodeError> {
+                let (payload, len) = decode_payload(bytes)?;
+                let amount = $ty::try_from_sat(<$inner>::from_be_bytes(payload))
+                    .map_err(|_| DecodeError::OutOfRange)?;
+                Ok((amount, len))
+            }
+        }
+    };
+}
+
+impl_encode!(Amount, u64);
+impl_encode!(SignedAmount, i64);
+
+/// The current version of the storage encoding.
+const ENCODING_VERSION: u8 = 1;
+/// The length of the current version of the storage encoding.
+const ENCODED_LEN: usize = 9;
+
+/// Reads the version of a storage encoding and its 8 bytes of satoshis.
+fn decode_payload(bytes: &[u8]) -> Result<([u8; 8], usize), DecodeError> {
+    match bytes.first() {
+        None => Err(DecodeError::UnexpectedEnd),
+        Some(&ENCODING_VERSION) => {
+            if bytes.len() < ENCODED_LEN {
+                return Err(DecodeError::UnexpectedEnd);
+            }
+            let mut payload = [0u8; 8];
+            payload.copy_from_slice(&bytes[1..ENCODED_LEN]);
+            Ok((payload, ENCODED_LEN))
+        }
+        Some(&version) => Err(DecodeError::UnknownVersion(version)),
+    }
+}
+
+/// Decodes 16 hex digits into 8 bytes.
+fn decode_hex(hex: &str) -> Result<[u8; 8], ParseHexError> {
+    if hex.len() != 16 {
+        return Err(ParseHexError::InvalidLength(hex.len()));
+    }
+
+    let mut bytes = [0u8; 8];
+    for (pos, c) in hex.bytes().enumerate() {
+        let nibble = match c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            b'A'..=b'F' => c - b'A' + 10,
+            _ => return Err(ParseHexError::InvalidCharacter { pos }),
+        };
+        bytes[pos / 2] |= nibble << if pos % 2 == 0 { 4 } else { 0 };
+    }
+    Ok(bytes)
+}
+
+/// An error decoding an amount from hex.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseHexError {
+    /// The string isn't 16 bytes long, the length is given.
+    InvalidLength(usize),
+    /// The string has a character that isn't a hex digit at the byte offset
+    /// `pos`.
+    InvalidCharacter {
+        /// The byte offset of the character.
+        pos: usize,
+    },
+    /// The amount is out of the valid money range, only returned with the
+    /// `strict-money` feature enabled.
+    OutOfRange,
+}
+
+impl Display for ParseHexError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match *self {
+            ParseHexError::InvalidLength(len) => write!(fmt, "expected 16 hex digits, got {}", len),
+            ParseHexError::InvalidCharacter { pos } => write!(fmt, "invalid hex digit at position {}", pos),
+            ParseHexError::OutOfRange => fmt.write_str("amount out of range"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for ParseHexError {
+    fn description(&self) -> &'static str {
+        match *self {
+            ParseHexError::InvalidLength(_) => "invalid hex length",
+            ParseHexError::InvalidCharacter { .. } => "invalid hex digit",
+            ParseHexError::OutOfRange => "amount out of range",
+        }
+    }
+}
+
+/// An error decoding an amount from its storage encoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The bytes end before the encoded amount does.
+    UnexpectedEnd,
+    /// The encoding has a version this crate doesn't know of, it's given.
+    UnknownVersion(u8),
+    /// The amount is out of the valid money range, only returned with the
+    /// `strict-money` feature enabled.
+    OutOfRange,
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match *self {
+            DecodeError::UnexpectedEnd => fmt.write_str("unexpected end of encoded amount"),
+            DecodeError::UnknownVersion(version) => write!(fmt, "unknown amount encoding version {}", version),
+            DecodeError::OutOfRange => fmt.write_str("amount out of range"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for DecodeError {
+    fn description(&self) -> &'static str {
+        match *self {
+            DecodeError::UnexpectedEnd => "unexpected end of encoded amount",
+            DecodeError::UnknownVersion(_) => "unknown amount encoding version",
+            DecodeError::OutOfRange => "amount out of range",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_le_bytes() {
+        let amount = Amount::from_sat(253583);
+        assert_eq!(amount.to_le_bytes(), [0x8f, 0xde, 0x03, 0, 0, 0, 0, 0]);
+        assert_eq!(Amount::from_le_bytes(amount.to_le_bytes()), Ok(amount));
+
+        let amount = SignedAmount::from_sat(-1);
+        assert_eq!(amount.to_le_bytes(), [0xff; 8]);
+        assert_eq!(SignedAmount::from_le_bytes([0xff; 8]), Ok(amount));
+
+        let max = Amount::from_le_bytes([0xff; 8]);
+        #[cfg(feature = "strict-money")]
+        assert_eq!(max, Err(OutOfRangeError(())));
+        #[cfg(not(feature = "strict-money"))]
+        assert_eq!(max, Ok(Amount::max_value()));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn encode_hex() {
+        let amount = Amount::from_sat(253583);
+        assert_eq!(amount.to_hex(), "8fde030000000000");
+        assert_eq!(Amount::from_hex("8fde030000000000"), Ok(amount));
+        assert_eq!(Amount::from_hex("8FDE030000000000"), Ok(amount));
+        assert_eq!(SignedAmount::from_sat(-2).to_hex(), "feffffffffffffff");
+
+        assert_eq!(Amount::from_hex("8fde03"), Err(ParseHexError::InvalidLength(6)));
+        assert_eq!(Amount::from_hex("8fde03000000000g"),
+                   Err(ParseHexError::InvalidCharacter { pos: 15 }));
+        #[cfg(feature = "strict-money")]
+        assert_eq!(Amount::from_hex("ffffffffffffffff"), Err(ParseHexError::OutOfRange));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn encode_storage() {
+        // The encodings are frozen, these must never change.
+        let amount = Amount::from_sat(253583);
+        assert_eq!(amount.encode_to_vec(), [1, 0, 0, 0, 0, 0, 0x03, 0xde, 0x8f]);
+        assert_eq!(Amount::decode_from_slice(&amount.encode_to_vec()), Ok((amount, 9)));
+        let amount = SignedAmount::from_sat(-1);
+        assert_eq!(amount.encode_to_vec(), [1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]);
+        assert_eq!(SignedAmount::decode_from_slice(&amount.encode_to_vec()), Ok((amount, 9)));
+
+        let bytes = [1, 0, 0, 0, 0, 0, 0, 0, 2, 0xaa];
+        assert_eq!(Amount::decode_from_slice(&bytes), Ok((Amount::from_sat(2), 9)));
+        assert!(Amount::from_sat(1).encode_to_vec() < Amount::from_sat(256).encode_to_vec());
+
+        assert_eq!(Amount::decode_from_slice(&[]), Err(DecodeError::UnexpectedEnd));
+        assert_eq!(Amount::decode_from_slice(&bytes[..8]), Err(DecodeError::UnexpectedEnd));
+        assert_eq!(Amount::decode_from_slice(&[2, 0]), Err(DecodeError::UnknownVersion(2)));
+        #[cfg(feature = "strict-money")]
+        assert_eq!(Amount::decode_from_slice(&[1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+                   Err(DecodeError::OutOfRange));
+    }
+}
@@ -0,0 +1,85 @@
+//! Conversions between amounts and `fixed` fixed-point numbers of BTC.
+//!
+//! Binary fractions can't represent every satoshi exactly, e.g. 0.00000001
+//! BTC, so amounts are converted to the nearest `I64F64`, whose error is
+//! below 2^-64 BTC, and then to the target type. Converting back rounds to
+//! the nearest satoshi, which makes round trips through types with enough
+//! fractional bits, 27 and above, exact.
+
+use std::convert::TryFrom;
+
+use fixed_crate::traits::Fixed;
+use fixed_crate::types::I64F64;
+
+use {Amount, OutOfRangeError, SignedAmount, SAT_PER_BTC};
+
+/// The number of fractional bits of `I64F64`.
+const FRAC_BITS: u32 = 64;
+
+/// Converts satoshis to the bits of the nearest `I64F64` number of BTC.
+fn sat_to_bits(sat: i128) -> i128 {
+    // Amounts are below 2^64 satoshis, so shifting their magnitude by 64 bits
+    // fits in an `u128`, as does the rounded quotient in an `i128`.
+    let per_btc = SAT_PER_BTC as u128;
+    let bits = ((sat.unsigned_abs() << FRAC_BITS) + per_btc / 2) / per_btc;
+    if sat < 0 { -(bits as i128) } else { bits as i128 }
+}
+
+/// Converts the bits of an `I64F64` number of BTC to the nearest satoshi,
+/// half-way cases away from zero.
+fn bits_to_sat(bits: i128) -> i128 {
+    let magnitude = bits.unsigned_abs();
+    let (btc, frac) = (magnitude >> FRAC_BITS, magnitude & ((1 << FRAC_BITS) - 1));
+    let per_btc = SAT_PER_BTC as u128;
+    let sat = btc * per_btc + ((frac * per_btc + (1 << (FRAC_BITS - 1))) >> FRAC_BITS);
+    if bits < 0 { -(sat as i128) } else { sat as i128 }
+}
+
+macro_rules! impl_fixed {
+    ($ty:ident) => {
+        impl $ty {
+            /// Converts this amount to a fixed-point number of BTC, rounded
+            /// to about 2^-64 BTC, returns `None` if it doesn't fit in `F`.
+            pub fn to_fixed_btc<F: Fixed>(self) -> Option<F> {
+                F::checked_from_num(I64F64::from_bits(sat_to_bits(i128::from(self))))
+            }
+
+            /// Converts a fixed-point number of BTC, rounded to the nearest
+            /// satoshi.
+            pub fn from_fixed_btc<F: Fixed>(btc: F) -> Result<$ty, OutOfRangeError> {
+                let btc = I64F64::checked_from_num(btc).ok_or(OutOfRangeError(()))?;
+                $ty::try_from(bits_to_sat(btc.to_bits()))
+            }
+        }
+    };
+}
+
+impl_fixed!(Amount);
+impl_fixed!(SignedAmount);
+
+#[cfg(test)]
+mod tests {
+    use fixed_crate::types::{I32F32, I64F64, U8F8};
+
+    use {Amount, SignedAmount};
+
+    #[test]
+    fn fixed_btc_conversions() {
+        let btc = Amount::from_sat(150_000_000).to_fixed_btc::<I64F64>().unwrap();
+        assert_eq!(btc, I64F64::from_num(1.5));
+        assert_eq!(Amount::from_fixed_btc(btc), Ok(Amount::from_sat(150_000_000)));
+
+        for &sat in &[1, 253583, 99_999_999, 2_100_000_000_000_000] {
+            let amount = Amount::from_sat(sat);
+            assert_eq!(Amount::from_fixed_btc(amount.to_fixed_btc::<I64F64>().unwrap()), Ok(amount));
+            assert_eq!(Amount::from_fixed_btc(amount.to_fixed_btc::<I32F32>().unwrap()), Ok(amount));
+        }
+        let amount = SignedAmount::from_sat(-253583);
+        assert_eq!(SignedAmount::from_fixed_btc(amount.to_fixed_btc::<I64F64>().unwrap()), Ok(amount));
+
+        assert_eq!(Amount::from_fixed_btc(I64F64::from_num(0.000000005)), Ok(Amount::from_sat(1)));
+        assert_eq!(SignedAmount::from_fixed_btc(I64F64::from_num(-0.000000005)), Ok(SignedAmount::from_sat(-1)));
+        assert!(Amount::from_fixed_btc(I64F64::from_num(-1)).is_err());
+        assert_eq!(Amount::from_sat(100_000_000_000).to_fixed_btc::<U8F8>(), None);
+    }
+}
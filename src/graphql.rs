@@ -0,0 +1,114 @@
+//! GraphQL scalars for amounts.
+//!
+//! `Amount` and `SignedAmount` are the `Amount` and `SignedAmount` scalars,
+//! decimal BTC strings such as `"0.00253583"`. `SatAmount` and
+//! `SatSignedAmount` wrap them to use strings of satoshis such as
+//! `"253583"` instead. Strings are used as GraphQL's `Int` is 32 bits and
+//! `Float` can't represent every amount.
+
+use async_graphql::{InputValueError, InputValueResult, Scalar, ScalarType, Value};
+
+use {Amount, SignedAmount};
+
+macro_rules! impl_btc_scalar {
+    ($ty:ident, $name:expr) => {
+        /// A decimal BTC string, e.g. `"0.00253583"`, parsed exactly.
+        #[Scalar(name = $name)]
+        impl ScalarType for $ty {
+            fn parse(value: Value) -> InputValueResult<$ty> {
+                match value {
+                    Value::String(ref s) => s.parse().map_err(InputValueError::custom),
+                    _ => Err(InputValueError::expected_type(value)),
+                }
+            }
+
+            fn is_valid(value: &Value) -> bool {
+                matches!(*value, Value::String(_))
+            }
+
+            fn to_value(&self) -> Value {
+                Value::String(self.to_string())
+            }
+        }
+    };
+}
+
+impl_btc_scalar!(Amount, "Amount");
+impl_btc_scalar!(SignedAmount, "SignedAmount");
+
+macro_rules! impl_sat_scalar {
+    ($wrapper:ident, $ty:ident, $name:expr) => {
+        /// Wraps an amount to use the GraphQL scalar of a string of
+        /// satoshis, e.g. `"253583"`.
+        #[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+        pub struct $wrapper(pub $ty);
+
+        /// A string of satoshis, e.g. `"253583"`.
+        #[Scalar(name = $name)]
+        impl ScalarType for $wrapper {
+            fn parse(value: Value) -> InputValueResult<$wrapper> {
+                match value {
+                    Value::String(ref s) => {
+                        $ty::from_sat_str(s).map($wrapper).map_err(InputValueError::custom)
+                    }
+                    _ => Err(InputValueError::expected_type(value)),
+                }
+            }
+
+            fn is_valid(value: &Value) -> bool {
+                matches!(*value, Value::String(_))
+            }
+
+            fn to_value(&self) -> Value {
+                Value::String(self.0.into_inner().to_string())
+            }
+        }
+
+        impl From<$ty> for $wrapper {
+            fn from(amt: $ty) -> $wrapper {
+                $wrapper(amt)
+            }
+        }
+
+        impl From<$wrapper> for $ty {
+            fn from(wrapper: $wrapper) -> $ty {
+                wrapper.0
+            }
+        }
+    };
+}
+
+impl_sat_scalar!(SatAmount, Amount, "SatAmount");
+impl_sat_scalar!(SatSignedAmount, SignedAmount, "SatSignedAmount");
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::{ScalarType, Value};
+
+    use super::*;
+
+    #[test]
+    fn graphql_btc_scalars() {
+        let amount = Amount::from_sat(253583);
+        assert_eq!(amount.to_value(), Value::String("0.00253583".to_owned()));
+        assert_eq!(<Amount as ScalarType>::parse(amount.to_value()).unwrap(), amount);
+        assert!(<Amount as ScalarType>::parse(Value::String("-1".to_owned())).is_err());
+        assert!(<Amount as ScalarType>::parse(Value::from(253583)).is_err());
+
+        let amount = SignedAmount::from_sat(-253583);
+        assert_eq!(amount.to_value(), Value::String("-0.00253583".to_owned()));
+        assert_eq!(<SignedAmount as ScalarType>::parse(amount.to_value()).unwrap(), amount);
+    }
+
+    #[test]
+    fn graphql_sat_scalars() {
+        let amount = SatAmount(Amount::from_sat(253583));
+        assert_eq!(amount.to_value(), Value::String("253583".to_owned()));
+        assert_eq!(SatAmount::parse(amount.to_value()).unwrap(), amount);
+        assert!(SatAmount::parse(Value::String("0.1".to_owned())).is_err());
+
+        let amount = SatSignedAmount::from(SignedAmount::from_sat(-253583));
+        assert_eq!(amount.to_value(), Value::String("-253583".to_owned()));
+        assert_eq!(SatSignedAmount::parse(amount.to_value()).unwrap(), amount);
+    }
+}
@@ -0,0 +1,139 @@
+//! Interoperability with rust-lightning (LDK).
+//!
+//! LDK represents amounts as `u64` millisatoshis in fields suffixed with
+//! `_msat`, which convert from and to `MilliSatoshi` with `From`. The
+//! functions here convert them from and to `Amount`s, and `HtlcLimits`
+//! reads the `htlc_minimum_msat` and `htlc_maximum_msat` limits of channels.
+
+use std::convert::TryFrom;
+
+use lightning::ln::channel_state::{ChannelCounterparty, ChannelDetails};
+use lightning::ln::msgs::UnsignedChannelUpdate;
+use lightning::routing::gossip::ChannelUpdateInfo;
+
+use {Amount, MilliSatoshi, OutOfRangeError, Rounding};
+
+/// Converts `amount` to the millisatoshis of an LDK `_msat` field, failing
+/// if they don't fit in an `u64`.
+pub fn to_msat(amount: Amount) -> Result<u64, OutOfRangeError> {
+    MilliSatoshi::try_from(amount).map(u64::from)
+}
+
+/// Converts the millisatoshis of an LDK `_msat` field to an `Amount`,
+/// rounded to a whole satoshi as specified by `rounding`, failing only with
+/// the `strict-money` feature enabled if it isn't valid money.
+pub fn from_msat(msat: u64, rounding: Rounding) -> Result<Amount, OutOfRangeError> {
+    MilliSatoshi::from_msat(msat).to_amount(rounding)
+}
+
+/// The limits on the value of the HTLCs of a channel, `None` where LDK
+/// doesn't know them.
+pub trait HtlcLimits {
+    /// The smallest HTLC the channel accepts, `htlc_minimum_msat`.
+    fn htlc_minimum(&self) -> Option<MilliSatoshi>;
+
+    /// The largest HTLC the channel accepts, `htlc_maximum_msat`.
+    fn htlc_maximum(&self) -> Option<MilliSatoshi>;
+
+    /// Returns `true` if an HTLC of `amount` is within the known limits.
+    fn accepts_htlc(&self, amount: MilliSatoshi) -> bool {
+        self.htlc_minimum().is_none_or(|min| amount >= min) &&
+            self.htlc_maximum().is_none_or(|max| amount <= max)
+    }
+
+    /// The smallest whole number of satoshis the channel accepts, the
+    /// minimum rounded up. With the `strict-money` feature enabled, fails if
+    /// it isn't valid money.
+    fn htlc_minimum_sat(&self) -> Result<Option<Amount>, OutOfRangeError> {
+        self.htlc_minimum().map(|min| min.to_amount(Rounding::Ceil)).transpose()
+    }
+
+    /// The largest whole number of satoshis the channel accepts, the maximum
+    /// rounded down. With the `strict-money` feature enabled, fails if it
+    /// isn't valid money.
+    fn htlc_maximum_sat(&self) -> Result<Option<Amount>, OutOfRangeError> {
+        self.htlc_maximum().map(|max| max.to_amount(Rounding::Floor)).transpose()
+    }
+}
+
+/// The limits of a channel direction in the network graph.
+impl HtlcLimits for ChannelUpdateInfo {
+    fn htlc_minimum(&self) -> Option<MilliSatoshi> {
+        Some(MilliSatoshi::from_msat(self.htlc_minimum_msat))
+    }
+
+    fn htlc_maximum(&self) -> Option<MilliSatoshi> {
+        Some(MilliSatoshi::from_msat(self.htlc_maximum_msat))
+    }
+}
+
+/// The limits announced in a `channel_update` message.
+impl HtlcLimits for UnsignedChannelUpdate {
+    fn htlc_minimum(&self) -> Option<MilliSatoshi> {
+        Some(MilliSatoshi::from_msat(self.htlc_minimum_msat))
+    }
+
+    fn htlc_maximum(&self) -> Option<MilliSatoshi> {
+        Some(MilliSatoshi::from_msat(self.htlc_maximum_msat))
+    }
+}
+
+/// The limits of the HTLCs we accept on a channel, its `inbound_htlc_*`
+/// fields.
+impl HtlcLimits for ChannelDetails {
+    fn htlc_minimum(&self) -> Option<MilliSatoshi> {
+        self.inbound_htlc_minimum_msat.map(MilliSatoshi::from_msat)
+    }
+
+    fn htlc_maximum(&self) -> Option<MilliSatoshi> {
+        self.inbound_htlc_maximum_msat.map(MilliSatoshi::from_msat)
+    }
+}
+
+/// The limits of the HTLCs the counterparty accepts, its
+/// `outbound_htlc_*` fields.
+impl HtlcLimits for ChannelCounterparty {
+    fn htlc_minimum(&self) -> Option<MilliSatoshi> {
+        self.outbound_htlc_minimum_msat.map(MilliSatoshi::from_msat)
+    }
+
+    fn htlc_maximum(&self) -> Option<MilliSatoshi> {
+        self.outbound_htlc_maximum_msat.map(MilliSatoshi::from_msat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lightning::routing::gossip::{ChannelUpdateInfo, RoutingFees};
+
+    use super::*;
+
+    #[test]
+    fn ldk_msat_conversions() {
+        assert_eq!(to_msat(Amount::from_sat(253_583)), Ok(253_583_000));
+        assert!(to_msat(Amount::max_value()).is_err());
+        assert_eq!(from_msat(253_583_500, Rounding::Floor), Ok(Amount::from_sat(253_583)));
+        assert_eq!(from_msat(253_583_500, Rounding::Ceil), Ok(Amount::from_sat(253_584)));
+        #[cfg(feature = "strict-money")]
+        assert_eq!(from_msat(u64::MAX, Rounding::Floor), Err(OutOfRangeError(())));
+    }
+
+    #[test]
+    fn ldk_htlc_limits() {
+        let info = ChannelUpdateInfo {
+            htlc_minimum_msat: 1_500,
+            htlc_maximum_msat: 99_999_999,
+            fees: RoutingFees { base_msat: 1_000, proportional_millionths: 100 },
+            last_update: 0,
+            cltv_expiry_delta: 144,
+            enabled: true,
+            last_update_message: None,
+        };
+        assert_eq!(info.htlc_minimum(), Some(MilliSatoshi::from_msat(1_500)));
+        assert_eq!(info.htlc_minimum_sat(), Ok(Some(Amount::from_sat(2))));
+        assert_eq!(info.htlc_maximum_sat(), Ok(Some(Amount::from_sat(99_999))));
+        assert!(info.accepts_htlc(MilliSatoshi::from_msat(1_500)));
+        assert!(!info.accepts_htlc(MilliSatoshi::from_msat(1_499)));
+        assert!(!info.accepts_htlc(MilliSatoshi::from_msat(100_000_000)));
+    }
+}
@@ -1,25 +1,185 @@
+#![cfg_attr(all(not(feature = "std"), not(test)), no_std)]
 #![warn(missing_docs)]
 
 //! # Bitcoin Amount
 //!
-
+//! ## Representation
+//!
+//! Amounts are stored as an integer number of satoshis: `Amount` wraps an
+//! `u64`, suitable for consensus values which are never negative, and
+//! `SignedAmount` wraps an `i64`, suitable for accounting where debits are
+//! negative. Conversions between both are checked.
+//!
+//! With the `rkyv` feature both types can be archived, `ArchivedAmount` and
+//! `ArchivedSignedAmount` are read in place from a little-endian integer.
+//!
+//! The width of the inner integer is intentionally not selectable through a
+//! cargo feature: features are additive, so enabling one in any crate of a
+//! dependency graph would change the type seen by every other crate using
+//! this one.
+//!
+//! ## `no_std`
+//!
+//! Without the default `std` feature the crate is `no_std`: amounts, their
+//! arithmetic, parsing and formatting only need `core`. The `alloc` feature
+//! adds the methods returning `String`s and `Vec`s, `std` the `Error`
+//! implementations, the floating point BTC conversions and the integrations
+//! with other crates.
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(any(feature = "std", test))]
+extern crate core;
+#[cfg(feature = "arbitrary")]
+extern crate arbitrary as arbitrary_crate;
+#[cfg(feature = "arrow")]
+extern crate arrow_array;
+#[cfg(feature = "async-graphql")]
+extern crate async_graphql;
+#[cfg(feature = "bdk")]
+extern crate bdk_chain;
+#[cfg(feature = "bigdecimal")]
+extern crate bigdecimal as bigdecimal_crate;
+#[cfg(feature = "bitcoin")]
+extern crate bitcoin as bitcoin_crate;
+#[cfg(feature = "bson")]
+extern crate bson as bson_crate;
+#[cfg(feature = "diesel")]
+extern crate diesel;
+#[cfg(feature = "fixed")]
+extern crate fixed as fixed_crate;
+#[cfg(feature = "ldk")]
+extern crate lightning;
+#[cfg(feature = "num-traits")]
+extern crate num_traits as num_traits_crate;
+#[cfg(any(test, feature = "proptest"))]
+extern crate proptest as proptest_crate;
+#[cfg(feature = "prost")]
+extern crate prost;
+#[cfg(feature = "quickcheck")]
+extern crate quickcheck as quickcheck_crate;
+#[cfg(feature = "rand")]
+extern crate rand as rand_crate;
+#[cfg(feature = "redis")]
+extern crate redis as redis_crate;
+#[cfg(feature = "rkyv")]
+extern crate rkyv;
+#[cfg(feature = "rust_decimal")]
+extern crate rust_decimal as rust_decimal_crate;
+#[cfg(feature = "rusqlite")]
+extern crate rusqlite as rusqlite_crate;
+#[cfg(feature = "schemars")]
+#[macro_use]
+extern crate schemars;
+#[cfg(all(test, feature = "serde"))]
+extern crate csv;
 #[cfg(feature = "serde")]
-extern crate serde;
+extern crate serde as serde_crate;
+#[cfg(all(test, feature = "serde"))]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_test;
+#[cfg(feature = "sea-orm")]
+extern crate sea_orm as sea_orm_crate;
 #[cfg(feature = "serde_json")]
 extern crate serde_json;
+#[cfg(feature = "sqlx")]
+extern crate sqlx as sqlx_crate;
 #[cfg(feature = "strason")]
 extern crate strason;
-
+#[cfg(feature = "utoipa")]
+extern crate utoipa;
+
+#[macro_use]
+mod macros;
+
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+mod basis_points;
+#[cfg(feature = "bdk")]
+pub mod bdk;
+#[cfg(feature = "bigdecimal")]
+mod bigdecimal;
+pub mod bip21;
+#[cfg(feature = "bitcoin")]
+mod bitcoin;
+pub mod bolt11;
+#[cfg(feature = "bson")]
+mod bson;
+pub mod consts;
+pub mod core_compat;
+mod denomination;
+#[cfg(feature = "diesel")]
+mod diesel_sql;
+mod display;
+mod encode;
+#[cfg(feature = "fixed")]
+mod fixed;
+#[cfg(feature = "async-graphql")]
+pub mod graphql;
+#[cfg(feature = "ldk")]
+pub mod ldk;
+#[doc(hidden)]
+#[macro_use]
+pub mod literal;
+mod millisatoshi;
+#[cfg(feature = "num-traits")]
+mod num_traits;
+mod parse;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+#[cfg(feature = "prost")]
+pub mod proto;
+#[cfg(feature = "quickcheck")]
+mod quickcheck;
+#[cfg(feature = "rand")]
+pub mod rand;
+#[cfg(feature = "redis")]
+mod redis;
+#[cfg(feature = "rust_decimal")]
+mod rust_decimal;
+#[cfg(feature = "rusqlite")]
+mod rusqlite;
+#[cfg(feature = "sea-orm")]
+mod sea_orm;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "locale")]
+pub mod locale;
+mod signed_amount;
+#[cfg(feature = "sqlx")]
+pub mod sqlx;
+#[cfg(feature = "alloc")]
+pub mod stats;
+pub mod subsidy;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+
+pub use basis_points::{BasisPoints, BPS_PER_UNIT};
+pub use denomination::{Denomination, ParseDenominationError};
+pub use display::DisplayAmount;
+pub use encode::{DecodeError, ParseHexError};
+pub use millisatoshi::{MilliSatoshi, MSAT_PER_SAT};
+pub use parse::{AmountParser, ParseAmountError};
+pub use signed_amount::SignedAmount;
+#[cfg(feature = "rkyv")]
+pub use signed_amount::ArchivedSignedAmount;
+
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::fmt::{self, Display, Formatter};
+#[cfg(feature = "std")]
 use std::error;
-use std::fmt::{self, Display, Formatter};
-
-use std::ops::{Add, Div, Mul, Sub};
 
-use std::num::ParseFloatError;
-use std::str::FromStr;
+use core::iter::Sum;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, Sub, SubAssign};
 
 /// The primitive type that holds the satoshis.
-type Inner = i64;
+type Inner = u64;
 
 /// The amount of satoshis in a BTC.
 pub const SAT_PER_BTC: i64 = 100_000_000;
@@ -28,196 +188,369 @@ pub const SAT_PER_BTC: i64 = 100_000_000;
 pub const SAT_PER_BTC_FP: f64 = 100_000_000.0;
 
 /// Maximum value in an `Amount`.
-pub const MAX: Amount = Amount(Inner::max_value());
+pub const MAX: Amount = Amount(Inner::MAX);
 /// Minimum value in an `Amount`.
-pub const MIN: Amount = Amount(Inner::min_value());
+pub const MIN: Amount = Amount(Inner::MIN);
+
+/// The direction in which a result is rounded to a whole satoshi.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Rounding {
+    /// Round towards negative infinity.
+    Floor,
+    /// Round towards positive infinity.
+    Ceil,
+    /// Round to the nearest satoshi, half-way cases away from zero.
+    Nearest,
+}
+
+/// Divides `num` by `den` rounding the quotient as specified by `rounding`,
+/// returns `None` if `den` is zero.
+fn div_rounded(num: i128, den: i128, rounding: Rounding) -> Option<i128> {
+    let (quot, rem) = (num.checked_div(den)?, num % den);
+    if rem == 0 {
+        return Some(quot);
+    }
 
-/// A bitcoin amount integer type.
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+    let sign = if (rem < 0) == (den < 0) { 1 } else { -1 };
+    let adjust = match rounding {
+        Rounding::Floor if sign < 0 => -1,
+        Rounding::Ceil if sign > 0 => 1,
+        Rounding::Nearest if rem.abs() * 2 >= den.abs() => sign,
+        _ => 0,
+    };
+
+    Some(quot + adjust)
+}
+
+/// An unsigned bitcoin amount integer type.
+///
+/// Most on-chain values, like transaction outputs, can never be negative and
+/// are represented by this type, use `SignedAmount` to represent debits.
+///
+/// Amounts can be added to and subtracted from each other, multiplied and
+/// divided by an `u64` scalar, and divided by another amount to get the
+/// (truncated) ratio between them.
+///
+/// # Overflow
+///
+/// The arithmetic operators (`+`, `-`, `*`, `/` and `%`) always panic on
+/// overflow, including going below zero, and on division by zero,
+/// regardless of whether the crate was compiled in debug or release mode,
+/// money values are never silently wrapped. Use the `checked_*`,
+/// `saturating_*`, `overflowing_*` or `wrapping_*` methods to handle
+/// overflow explicitly.
+#[derive(Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize))]
+#[cfg_attr(feature = "rkyv", rkyv(derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)))]
+#[cfg_attr(feature = "diesel", derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow))]
+#[cfg_attr(feature = "diesel", diesel(sql_type = diesel::sql_types::BigInt))]
 pub struct Amount(Inner);
 
+impl_amount!(Amount, Inner);
+
+#[cfg(feature = "rkyv")]
+impl ArchivedAmount {
+    /// Reads the archived amount without deserializing it.
+    pub fn to_native(&self) -> Amount {
+        Amount(self.0.to_native())
+    }
+}
+
 impl Amount {
     /// Creates an `Amount` from the given type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is negative, use `SignedAmount::from_btc` for
+    /// values that can be negative.
     pub fn from_btc<T>(btc: T) -> Amount
     where T:
           IntoBtc,
     {
-        btc.into_btc()
+        Amount::try_from(btc.into_btc()).expect("Amount::from_btc with a negative value")
     }
 
-    /// Creates a new `Amount` from a satoshi amount.
-    pub fn from_sat(sat: Inner) -> Amount {
-        Amount(sat)
+    /// Creates an `Amount` from a floating point BTC value.
+    ///
+    /// Returns an error if `btc` is NaN, infinite, negative or its satoshi
+    /// equivalent doesn't fit in an `Amount`, or with the `strict-money`
+    /// feature enabled, isn't valid money.
+    #[cfg(feature = "std")]
+    pub fn try_from_btc(btc: f64) -> Result<Amount, FromBtcError> {
+        let sat = try_round_to_sat(btc)?;
+        if !(0.0..18_446_744_073_709_551_616.0).contains(&sat) {
+            return Err(FromBtcError::OutOfRange);
+        }
+
+        Amount::try_from_sat(sat as Inner).map_err(|_| FromBtcError::OutOfRange)
     }
 
-    /// Returns the additive identity of `Amount`.
-    pub fn zero() -> Amount {
-        Amount(0)
+    /// Converts this amount to a `SignedAmount`, returns an error if it's
+    /// greater than `SignedAmount::max_value()`.
+    pub fn to_signed(self) -> Result<SignedAmount, OutOfRangeError> {
+        i64::try_from(self.0).map_err(|_| OutOfRangeError(())).and_then(SignedAmount::try_from_sat)
     }
 
-    /// Returns the multiplicative identity of `Amount`.
-    pub fn one() -> Amount {
-        Amount(1)
+    /// Computes the absolute difference between `self` and `other`.
+    pub const fn abs_diff(self, other: Amount) -> Amount {
+        Amount(self.0.abs_diff(other.0))
     }
+}
 
-    /// Maximum value that can fit in an `Amount`.
-    pub fn max_value() -> Amount { MAX }
+/// Multiplies the satoshis of both amounts.
+///
+/// **Deprecated**: the product of two amounts has no meaning as money, use
+/// `Mul<u64>` to scale an amount instead. This implementation will be
+/// removed in the next major release.
+impl Mul for Amount {
+    type Output = Amount;
+
+    fn mul(self, rhs: Amount) -> Self::Output {
+        self.checked_mul(rhs.0).expect("Amount multiplication overflow")
+    }
+}
+
+/// Extension trait to sum iterators of amounts without risk of overflow.
+pub trait CheckedSum<T> {
+    /// Sums all the amounts, returns `None` if the sum overflowed.
+    fn checked_sum(self) -> Option<T>;
+}
 
-    /// Minimum value that can fit in an `Amount`.
-    pub fn min_value() -> Amount { MIN }
+impl TryFrom<SignedAmount> for Amount {
+    type Error = NegativeAmountError;
 
-    /// Converts this `Amount` to the inner satoshis.
-    pub fn into_inner(self) -> Inner {
-        self.0
+    fn try_from(amt: SignedAmount) -> Result<Amount, NegativeAmountError> {
+        amt.to_unsigned()
     }
 }
 
-impl Add for Amount {
-    type Output = Amount;
-    
-    fn add(self, rhs: Amount) -> Self::Output {
-        Amount::from_sat(self.0 + rhs.0)
+impl TryFrom<Amount> for SignedAmount {
+    type Error = OutOfRangeError;
+
+    fn try_from(amt: Amount) -> Result<SignedAmount, OutOfRangeError> {
+        amt.to_signed()
     }
 }
 
-impl Div for Amount {
-    type Output = Amount;
-    
-    fn div(self, rhs: Amount) -> Self::Output {
-        Amount::from_sat(self.0 / rhs.0)
+impl From<u32> for Amount {
+    fn from(sat: u32) -> Amount {
+        Amount::from_sat(Inner::from(sat))
     }
 }
 
-impl Mul for Amount {
-    type Output = Amount;
-    
-    fn mul(self, rhs: Amount) -> Self::Output {
-        Amount::from_sat(self.0 * rhs.0)
+impl From<u64> for Amount {
+    fn from(sat: u64) -> Amount {
+        Amount::from_sat(sat)
     }
 }
 
-impl Sub for Amount {
-    type Output = Amount;
-    
-    fn sub(self, rhs: Amount) -> Self::Output {
-        Amount::from_sat(self.0 - rhs.0)
+impl TryFrom<i64> for Amount {
+    type Error = OutOfRangeError;
+
+    fn try_from(sat: i64) -> Result<Amount, OutOfRangeError> {
+        Inner::try_from(sat).map_err(|_| OutOfRangeError(())).and_then(Amount::try_from_sat)
     }
 }
 
-#[cfg(feature = "serde")]
-impl<'de> serde::Deserialize<'de> for Amount {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: serde::de::Deserializer<'de>
-    {
-        Inner::deserialize(deserializer).map(Amount)
+impl TryFrom<i128> for Amount {
+    type Error = OutOfRangeError;
+
+    fn try_from(sat: i128) -> Result<Amount, OutOfRangeError> {
+        Inner::try_from(sat).map_err(|_| OutOfRangeError(())).and_then(Amount::try_from_sat)
     }
 }
 
-#[cfg(feature = "serde")]
-impl serde::Serialize for Amount {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::ser::Serializer
-    {
-        Inner::serialize(&self.0, serializer)
+impl From<Amount> for u64 {
+    fn from(amt: Amount) -> u64 {
+        amt.0
     }
 }
 
-impl FromStr for Amount {
-    type Err = ParseAmountError;
+impl From<Amount> for i128 {
+    fn from(amt: Amount) -> i128 {
+        i128::from(amt.0)
+    }
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let btc = f64::from_str(s).map_err(ParseAmountError)?;
+impl TryFrom<Amount> for i64 {
+    type Error = OutOfRangeError;
 
-        Ok(Amount::from_btc(btc))
+    fn try_from(amt: Amount) -> Result<i64, OutOfRangeError> {
+        i64::try_from(amt.0).map_err(|_| OutOfRangeError(()))
     }
 }
 
-/// An error during `Amount` parsing.
-#[derive(Debug)]
-pub struct ParseAmountError(ParseFloatError);
+/// An error converting a `SignedAmount` to an `Amount`, the value is
+/// negative.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NegativeAmountError(());
 
-impl Display for ParseAmountError {
+impl Display for NegativeAmountError {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
-        write!(fmt, "invalid floating point integer: {}", self.0)
+        write!(fmt, "amount is negative")
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for NegativeAmountError {
+    fn description(&self) -> &'static str {
+        "negative amount"
     }
 }
 
-impl error::Error for ParseAmountError {
-    fn cause(&self) -> Option<&error::Error> {
-        Some(&self.0)
+/// An error converting to or from an amount type, the value doesn't fit in
+/// the target type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutOfRangeError(());
+
+impl Display for OutOfRangeError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(fmt, "amount out of range of the target type")
     }
+}
 
+#[cfg(feature = "std")]
+impl error::Error for OutOfRangeError {
     fn description(&self) -> &'static str {
-        "floating point error"
+        "amount out of range"
+    }
+}
+
+/// An error creating an amount from a floating point BTC value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FromBtcError {
+    /// The value is NaN.
+    NotANumber,
+    /// The value is positive or negative infinity.
+    Infinite,
+    /// The value in satoshis doesn't fit in the amount type.
+    OutOfRange,
+}
+
+impl Display for FromBtcError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match *self {
+            FromBtcError::NotANumber => write!(fmt, "BTC value is not a number"),
+            FromBtcError::Infinite => write!(fmt, "BTC value is infinite"),
+            FromBtcError::OutOfRange => write!(fmt, "BTC value out of range of the amount type"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for FromBtcError {
+    fn description(&self) -> &'static str {
+        match *self {
+            FromBtcError::NotANumber => "not a number",
+            FromBtcError::Infinite => "infinite value",
+            FromBtcError::OutOfRange => "value out of range",
+        }
+    }
+}
+
+/// Converts `btc` to satoshis, rounding half-way cases away from zero, the
+/// result is not range checked.
+#[cfg(feature = "std")]
+fn try_round_to_sat(btc: f64) -> Result<f64, FromBtcError> {
+    if btc.is_nan() {
+        Err(FromBtcError::NotANumber)
+    } else if btc.is_infinite() {
+        Err(FromBtcError::Infinite)
+    } else {
+        Ok((btc * SAT_PER_BTC_FP).round())
     }
 }
 
-fn round_and_to_sat(v: f64) -> Inner {
+fn round_and_to_sat(v: f64) -> i64 {
     if v < 0.0 {
-        ((v * SAT_PER_BTC_FP) - 0.5) as Inner
+        ((v * SAT_PER_BTC_FP) - 0.5) as i64
     } else {
-        ((v * SAT_PER_BTC_FP) + 0.5) as Inner
+        ((v * SAT_PER_BTC_FP) + 0.5) as i64
     }
 }
 
-/// Trait to mark types as convertable into `Amount`s.
+/// Trait to mark types as convertable into amounts.
 ///
 /// Types that implement this trait should perform the conversion from BTC
 /// amounts to satoshis e.g. an f64 performs the conversion of "0.00000025" to
 /// 25 satoshis. See `Amount::from_sat`.
 pub trait IntoBtc {
     /// Performs the conversion.
-    fn into_btc(self) -> Amount;
+    fn into_btc(self) -> SignedAmount;
 }
 
-impl<'a> IntoBtc for &'a f64 {
-    fn into_btc(self) -> Amount {
+impl IntoBtc for &f64 {
+    fn into_btc(self) -> SignedAmount {
         let sat = round_and_to_sat(*self);
-        Amount::from_sat(sat)
+        SignedAmount::from_sat(sat)
     }
 }
 
 impl IntoBtc for f64 {
-    fn into_btc(self) -> Amount {
+    fn into_btc(self) -> SignedAmount {
         let sat = round_and_to_sat(self);
-        Amount::from_sat(sat)
+        SignedAmount::from_sat(sat)
     }
 }
 
+/// Parses the number exactly.
+///
+/// # Panics
+///
+/// Panics if the number has sub-satoshi precision or is out of range, use
+/// `Amount::from_serde_json` or `SignedAmount::from_serde_json` to handle
+/// the error instead.
 #[cfg(feature = "serde_json")]
-impl<'a> IntoBtc for &'a serde_json::value::Number {
-    fn into_btc(self) -> Amount {
-        let num = format!("{}", self);
-        Amount::from_str(&*num).unwrap()
+impl IntoBtc for &serde_json::value::Number {
+    fn into_btc(self) -> SignedAmount {
+        SignedAmount::from_serde_json(self).expect("invalid serde_json number of BTC")
     }
 }
 
+/// Parses the number exactly.
+///
+/// # Panics
+///
+/// Panics if the number has sub-satoshi precision or is out of range, use
+/// `Amount::from_serde_json` or `SignedAmount::from_serde_json` to handle
+/// the error instead.
 #[cfg(feature = "serde_json")]
 impl IntoBtc for serde_json::value::Number {
-    fn into_btc(self) -> Amount {
-        let num = format!("{}", self);
-        Amount::from_str(&*num).unwrap()
+    fn into_btc(self) -> SignedAmount {
+        (&self).into_btc()
     }
 }
 
+/// Parses the number exactly.
+///
+/// # Panics
+///
+/// Panics if the value isn't a number, has sub-satoshi precision or is out
+/// of range, use `Amount::from_strason_json` or
+/// `SignedAmount::from_strason_json` to handle the error instead.
 #[cfg(feature = "strason")]
-impl<'a> IntoBtc for &'a strason::Json {
-    fn into_btc(self) -> Amount {
-        Amount::from_str(self.num().unwrap()).unwrap()
+impl IntoBtc for &strason::Json {
+    fn into_btc(self) -> SignedAmount {
+        SignedAmount::from_strason_json(self).expect("invalid strason number of BTC")
     }
 }
 
+/// Parses the number exactly.
+///
+/// # Panics
+///
+/// Panics if the value isn't a number, has sub-satoshi precision or is out
+/// of range, use `Amount::from_strason_json` or
+/// `SignedAmount::from_strason_json` to handle the error instead.
 #[cfg(feature = "strason")]
-impl IntoBtc for  strason::Json {
-    fn into_btc(self) -> Amount {
-        Amount::from_str(self.num().unwrap()).unwrap()
+impl IntoBtc for strason::Json {
+    fn into_btc(self) -> SignedAmount {
+        (&self).into_btc()
     }
 }
 
 #[cfg(test)]
-pub mod tests {
+mod tests {
     use std::str::FromStr;
 
     use super::*;
@@ -243,9 +576,402 @@ pub mod tests {
     #[test]
     fn amount_add_div_mul_sub() {
         let res = ((Amount::from_btc(0.0025) +
-                    Amount::from_btc(0.0005)) * (Amount::from_btc(2.0))) /
-                    Amount::from_btc(2.0);
+                    Amount::from_btc(0.0005)) * 4) / 2 -
+                    Amount::from_btc(0.003);
 
         assert_eq!(res, Amount::from_btc(0.003));
+        assert_eq!(Amount::from_btc(0.003) / Amount::from_btc(0.001), 3);
+        assert_eq!(Amount::from_btc(0.003) / Amount::from_btc(0.002), 1);
+    }
+
+    #[test]
+    fn amount_checked_ops() {
+        let one = Amount::from_sat(1);
+        assert_eq!(MAX.checked_add(one), None);
+        assert_eq!(MIN.checked_sub(one), None);
+        assert_eq!(MAX.checked_mul(2), None);
+        assert_eq!(one.checked_div(0), None);
+
+        assert_eq!(Amount::from_sat(5).checked_add(one), Some(Amount::from_sat(6)));
+        assert_eq!(Amount::from_sat(5).checked_sub(one), Some(Amount::from_sat(4)));
+        assert_eq!(Amount::from_sat(5).checked_mul(3), Some(Amount::from_sat(15)));
+        assert_eq!(Amount::from_sat(7).checked_div(2), Some(Amount::from_sat(3)));
+    }
+
+    #[test]
+    fn amount_saturating_ops() {
+        let one = Amount::from_sat(1);
+        assert_eq!(MAX.saturating_add(one), MAX);
+        assert_eq!(MIN.saturating_sub(one), MIN);
+        assert_eq!(MAX.saturating_mul(2), MAX);
+
+        assert_eq!(Amount::from_sat(5).saturating_add(one), Amount::from_sat(6));
+        assert_eq!(Amount::from_sat(5).saturating_sub(one), Amount::from_sat(4));
+        assert_eq!(Amount::from_sat(5).saturating_mul(3), Amount::from_sat(15));
+    }
+
+    #[test]
+    fn amount_overflowing_ops() {
+        let one = Amount::from_sat(1);
+        assert_eq!(MAX.overflowing_add(one), (MIN, true));
+        assert_eq!(MIN.overflowing_sub(one), (MAX, true));
+        assert_eq!(MAX.overflowing_mul(2), (Amount(Inner::MAX - 1), true));
+
+        assert_eq!(Amount::from_sat(5).overflowing_add(one), (Amount::from_sat(6), false));
+        assert_eq!(Amount::from_sat(5).overflowing_sub(one), (Amount::from_sat(4), false));
+        assert_eq!(Amount::from_sat(5).overflowing_mul(3), (Amount::from_sat(15), false));
+    }
+
+    #[test]
+    fn amount_wrapping_unchecked_ops() {
+        let one = Amount::from_sat(1);
+        assert_eq!(MAX.wrapping_add(one), MIN);
+        assert_eq!(MIN.wrapping_sub(one), MAX);
+        assert_eq!(MAX.wrapping_mul(2), Amount(Inner::MAX - 1));
+
+        let (a, b) = (Amount::from_sat(5), Amount::from_sat(3));
+        unsafe {
+            assert_eq!(a.unchecked_add(b), Amount::from_sat(8));
+            assert_eq!(a.unchecked_sub(b), Amount::from_sat(2));
+            assert_eq!(a.unchecked_mul(3), Amount::from_sat(15));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Amount addition overflow")]
+    fn amount_add_overflow_panics() {
+        let _ = MAX + Amount::from_sat(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Amount subtraction overflow")]
+    fn amount_sub_overflow_panics() {
+        let _ = MIN - Amount::from_sat(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Amount multiplication overflow")]
+    fn amount_mul_overflow_panics() {
+        let _ = MAX * 2;
+    }
+
+    #[test]
+    #[should_panic(expected = "Amount division overflow or division by zero")]
+    fn amount_div_by_zero_panics() {
+        let _ = Amount::from_sat(1) / 0;
+    }
+
+    #[test]
+    fn amount_rem_div_rem() {
+        let amt = Amount::from_sat(1_000_003);
+        assert_eq!(amt % 10, Amount::from_sat(3));
+        assert_eq!(amt.checked_rem(0), None);
+
+        let (quot, rem) = amt.div_rem(4);
+        assert_eq!(quot, Amount::from_sat(250_000));
+        assert_eq!(rem, Amount::from_sat(3));
+        assert_eq!(quot * 4 + rem, amt);
+    }
+
+    #[test]
+    #[should_panic(expected = "Amount::from_btc with a negative value")]
+    fn amount_from_negative_btc_panics() {
+        let _ = Amount::from_btc(-0.1);
+    }
+
+    #[test]
+    fn amount_from_negative_str() {
+        assert_eq!(Amount::from_str("-0.1"), Err(ParseAmountError::Negative));
+        assert_eq!(Amount::from_str("-0").unwrap(), Amount::zero());
+    }
+
+    #[test]
+    fn amount_from_str_exact() {
+        assert_eq!(Amount::from_str("20000000.00000001").unwrap(), Amount::from_sat(2_000_000_000_000_001));
+        #[cfg(feature = "strict-money")]
+        assert_eq!(Amount::from_str("184467440737.09551615"), Err(ParseAmountError::OutOfRange));
+        #[cfg(not(feature = "strict-money"))]
+        assert_eq!(Amount::from_str("184467440737.09551615"), Ok(Amount::max_value()));
+        assert_eq!(Amount::from_str("184467440737.09551616"), Err(ParseAmountError::OutOfRange));
+        assert_eq!(Amount::from_str("0.000000001"), Err(ParseAmountError::TooPrecise));
+    }
+
+    #[test]
+    fn amount_from_str_too_precise() {
+        assert_eq!(Amount::from_str("0.123456789"), Err(ParseAmountError::TooPrecise));
+        assert_eq!(Amount::from_str("0.123456785"), Err(ParseAmountError::TooPrecise));
+        assert_eq!(Amount::from_str("1.000000001"), Err(ParseAmountError::TooPrecise));
+        assert_eq!(Amount::from_str("0.00000000999"), Err(ParseAmountError::TooPrecise));
+        // Zeros past the 8th decimal place don't discard anything.
+        assert_eq!(Amount::from_str("0.123456780"), Ok(Amount::from_sat(12_345_678)));
+    }
+
+    #[test]
+    fn amount_assign_ops() {
+        let mut balance = Amount::from_sat(1000);
+        balance += Amount::from_sat(500);
+        assert_eq!(balance, Amount::from_sat(1500));
+        balance -= Amount::from_sat(100);
+        assert_eq!(balance, Amount::from_sat(1400));
+        balance *= 3;
+        assert_eq!(balance, Amount::from_sat(4200));
+        balance /= 4;
+        assert_eq!(balance, Amount::from_sat(1050));
+    }
+
+    #[test]
+    #[should_panic(expected = "Amount addition overflow")]
+    fn amount_add_assign_overflow_panics() {
+        let mut balance = MAX;
+        balance += Amount::from_sat(1);
+    }
+
+    #[test]
+    fn amount_ref_ops() {
+        let amounts = [Amount::from_sat(300), Amount::from_sat(100)];
+        let (a, b) = (&amounts[0], &amounts[1]);
+        let scalars = [2, 3];
+        let (two, three) = (&scalars[0], &scalars[1]);
+        assert_eq!(a + b, Amount::from_sat(400));
+        assert_eq!(*a + b, Amount::from_sat(400));
+        assert_eq!(a - *b, Amount::from_sat(200));
+        assert_eq!(a * two, Amount::from_sat(600));
+        assert_eq!(*a / three, Amount::from_sat(100));
+
+        let mut total = Amount::zero();
+        for amt in &amounts {
+            total += amt;
+        }
+        assert_eq!(total, Amount::from_sat(400));
+    }
+
+    #[test]
+    fn amount_sum() {
+        let amounts = [Amount::from_sat(100), Amount::from_sat(200), Amount::from_sat(300)];
+        assert_eq!(amounts.iter().sum::<Amount>(), Amount::from_sat(600));
+        assert_eq!(amounts.iter().cloned().sum::<Amount>(), Amount::from_sat(600));
+        assert_eq!(amounts.iter().checked_sum(), Some(Amount::from_sat(600)));
+        assert_eq!(Vec::<Amount>::new().into_iter().checked_sum(), Some(Amount::zero()));
+        assert_eq!(vec![MAX, Amount::from_sat(1)].into_iter().checked_sum(), None);
+    }
+
+    #[test]
+    fn amount_abs_diff() {
+        let (a, b) = (Amount::from_sat(100), Amount::from_sat(450));
+        assert_eq!(a.abs_diff(b), Amount::from_sat(350));
+        assert_eq!(b.abs_diff(a), Amount::from_sat(350));
+        assert_eq!(a.abs_diff(a), Amount::zero());
+        assert_eq!(MAX.abs_diff(MIN), MAX);
+    }
+
+    #[test]
+    fn amount_div_rounding() {
+        let amt = Amount::from_sat(10);
+        assert_eq!(amt.div_floor(3), Amount::from_sat(3));
+        assert_eq!(amt.div_ceil(3), Amount::from_sat(4));
+        assert_eq!(amt.div_round(3), Amount::from_sat(3));
+        assert_eq!(amt.div_round(4), Amount::from_sat(3));
+        assert_eq!(amt.div_round(6), Amount::from_sat(2));
+
+        assert_eq!(Amount::from_sat(9).div_floor(3), Amount::from_sat(3));
+        assert_eq!(Amount::from_sat(9).div_ceil(3), Amount::from_sat(3));
+        assert_eq!(MAX.div_round(Inner::MAX), Amount::from_sat(1));
+        assert_eq!(MAX.div_ceil(2), Amount(Inner::MAX / 2 + 1));
+    }
+
+    #[test]
+    fn amount_midpoint_mean() {
+        assert_eq!(Amount::from_sat(100).midpoint(Amount::from_sat(201)), Amount::from_sat(150));
+        assert_eq!(Amount::midpoint(Amount::from_sat(3), Amount::zero()), Amount::from_sat(1));
+        assert_eq!(MAX.midpoint(MAX), MAX);
+        assert_eq!(MIN.midpoint(MAX), Amount(Inner::MAX / 2));
+
+        assert_eq!(Amount::mean(&[]), None);
+        assert_eq!(Amount::mean(&[MAX, MAX, MAX]), Some(MAX));
+        assert_eq!(Amount::mean(&[Amount::from_sat(1), Amount::from_sat(2), Amount::from_sat(4)]),
+                   Some(Amount::from_sat(2)));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn amount_split_evenly() {
+        let parts = Amount::from_sat(10).split_evenly(3);
+        assert_eq!(parts, vec![Amount::from_sat(4), Amount::from_sat(3), Amount::from_sat(3)]);
+
+        let parts = MAX.split_evenly(7);
+        assert_eq!(parts.iter().checked_sum(), Some(MAX));
+        assert_eq!(Amount::from_sat(2).split_evenly(5).iter().sum::<Amount>(), Amount::from_sat(2));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn amount_split_weighted() {
+        let parts = Amount::from_sat(100).split_weighted(&[1, 1, 1]);
+        assert_eq!(parts, vec![Amount::from_sat(34), Amount::from_sat(33), Amount::from_sat(33)]);
+
+        let parts = Amount::from_sat(1000).split_weighted(&[50, 30, 20, 0]);
+        assert_eq!(parts, vec![Amount::from_sat(500), Amount::from_sat(300),
+                               Amount::from_sat(200), Amount::zero()]);
+
+        let parts = Amount::from_sat(10).split_weighted(&[2, 3, 5, 7]);
+        assert_eq!(parts, vec![Amount::from_sat(1), Amount::from_sat(2),
+                               Amount::from_sat(3), Amount::from_sat(4)]);
+
+        let parts = MAX.split_weighted(&[u64::MAX, 1, u64::MAX]);
+        assert_eq!(parts.iter().checked_sum(), Some(MAX));
+    }
+
+    #[test]
+    fn amount_predicates_and_bounds() {
+        assert!(Amount::zero().is_zero());
+        assert!(!Amount::zero().is_positive());
+        assert!(Amount::from_sat(1).is_positive());
+        assert!(!Amount::from_sat(1).is_zero());
+
+        let (dust, limit) = (Amount::from_sat(546), Amount::from_sat(100_000));
+        assert_eq!(Amount::from_sat(10).clamp(dust, limit), dust);
+        assert_eq!(Amount::from_sat(1_000).clamp(dust, limit), Amount::from_sat(1_000));
+        assert_eq!(MAX.clamp(dust, limit), limit);
+        assert_eq!(dust.min(limit), dust);
+        assert_eq!(dust.max(limit), limit);
+    }
+
+    #[test]
+    fn amount_const_fn() {
+        const FEE: Amount = Amount::from_sat(1_000);
+        const CHANGE: Option<Amount> = Amount::from_sat(100_000).checked_sub(FEE);
+        const OVERFLOW: Option<Amount> = FEE.checked_mul(u64::MAX);
+        const DEBIT: SignedAmount = SignedAmount::from_sat(-500);
+        const LIMIT: Amount = FEE.max(DEBIT.unsigned_abs()).clamp(Amount::zero(), Amount::MAX_MONEY);
+
+        assert_eq!(CHANGE, Some(Amount::from_sat(99_000)));
+        assert_eq!(OVERFLOW, None);
+        assert_eq!(LIMIT, FEE);
+        assert_eq!(DEBIT.checked_neg(), Some(SignedAmount::from_sat(500)));
+        assert!(DEBIT.is_negative() && FEE.is_positive() && FEE.is_valid_money());
+        assert_eq!(FEE.into_inner(), 1_000);
+    }
+
+    #[test]
+    fn amount_max_money() {
+        assert_eq!(Amount::MAX_MONEY, Amount::from_sat(2_100_000_000_000_000));
+        assert!(Amount::MAX_MONEY.is_valid_money());
+        assert!(Amount::zero().is_valid_money());
+        assert!(!MAX.is_valid_money());
+        assert!(!Amount::MAX_MONEY.wrapping_add(Amount::from_sat(1)).is_valid_money());
+    }
+
+    #[test]
+    #[cfg(feature = "strict-money")]
+    #[should_panic(expected = "Amount out of the valid money range")]
+    fn amount_strict_money_panics() {
+        let _ = Amount::MAX_MONEY + Amount::from_sat(1);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn amount_try_from_btc() {
+        assert_eq!(Amount::try_from_btc(0.00253583), Ok(Amount::from_sat(253583)));
+        assert_eq!(Amount::try_from_btc(0.0), Ok(Amount::zero()));
+        assert_eq!(Amount::try_from_btc(f64::NAN), Err(FromBtcError::NotANumber));
+        assert_eq!(Amount::try_from_btc(f64::INFINITY), Err(FromBtcError::Infinite));
+        assert_eq!(Amount::try_from_btc(f64::NEG_INFINITY), Err(FromBtcError::Infinite));
+        assert_eq!(Amount::try_from_btc(-0.1), Err(FromBtcError::OutOfRange));
+        assert_eq!(Amount::try_from_btc(1e12), Err(FromBtcError::OutOfRange));
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn amount_to_serde_json() {
+        assert_eq!(Amount::from_sat(253583).to_serde_json().to_string(), "0.00253583");
+        assert_eq!(Amount::from_sat(2099999999999999).to_serde_json().to_string(),
+                   "20999999.99999999");
+        assert_eq!(SignedAmount::from_sat(-1).to_serde_json().to_string(), "-0.00000001");
+    }
+
+    #[cfg(feature = "rkyv")]
+    #[test]
+    fn amount_rkyv_archive() {
+        use rkyv::rancor::Error;
+        use rkyv::vec::ArchivedVec;
+
+        let amounts = vec![Amount::from_sat(253583), Amount::zero(), Amount::MAX_MONEY];
+        let bytes = rkyv::to_bytes::<Error>(&amounts).unwrap();
+        let archived = rkyv::access::<ArchivedVec<ArchivedAmount>, Error>(&bytes).unwrap();
+        assert_eq!(archived[0].to_native(), Amount::from_sat(253583));
+        assert_eq!(archived.iter().map(ArchivedAmount::to_native).collect::<Vec<_>>(), amounts);
+        assert_eq!(rkyv::deserialize::<Vec<Amount>, Error>(archived).unwrap(), amounts);
+
+        let signed = SignedAmount::from_sat(-253583);
+        let bytes = rkyv::to_bytes::<Error>(&signed).unwrap();
+        let archived = rkyv::access::<ArchivedSignedAmount, Error>(&bytes).unwrap();
+        assert_eq!(archived.to_native(), signed);
+    }
+
+    #[cfg(feature = "strason")]
+    #[test]
+    fn amount_to_strason_json() {
+        assert_eq!(Amount::from_sat(253583).to_strason_json().num(), Some("0.00253583"));
+        assert_eq!(SignedAmount::from_sat(-2099999999999999).to_strason_json().num(),
+                   Some("-20999999.99999999"));
+        let json = Amount::from_sat(1).to_strason_json();
+        assert_eq!(Amount::from_strason_json(&json), Ok(Amount::from_sat(1)));
+    }
+
+    #[test]
+    fn amount_to_signed() {
+        assert_eq!(Amount::from_sat(5).to_signed(), Ok(SignedAmount::from_sat(5)));
+        assert_eq!(MIN.to_signed(), Ok(SignedAmount::zero()));
+        #[cfg(feature = "strict-money")]
+        assert_eq!(Amount(i64::MAX as u64).to_signed(), Err(OutOfRangeError(())));
+        #[cfg(not(feature = "strict-money"))]
+        assert_eq!(Amount(i64::MAX as u64).to_signed(), Ok(SignedAmount::max_value()));
+        assert_eq!(Amount(i64::MAX as u64 + 1).to_signed(), Err(OutOfRangeError(())));
+        assert_eq!(MAX.to_signed(), Err(OutOfRangeError(())));
+    }
+
+    #[test]
+    fn amount_default_hash() {
+        use std::collections::HashMap;
+
+        assert_eq!(Amount::default(), Amount::zero());
+        assert_eq!(SignedAmount::default(), SignedAmount::zero());
+
+        let mut outputs = HashMap::new();
+        for amt in &[Amount::from_sat(546), Amount::from_sat(1000), Amount::from_sat(546)] {
+            *outputs.entry(*amt).or_insert(0) += 1;
+        }
+        assert_eq!(outputs[&Amount::from_sat(546)], 2);
+        assert_eq!(outputs[&Amount::from_sat(1000)], 1);
+    }
+
+    #[test]
+    fn amount_cmp_sat() {
+        let fee = Amount::from_sat(150_000);
+        assert!(fee > 100_000);
+        assert!(fee <= 150_000);
+        assert!(fee == 150_000);
+        assert!(100_000 < fee);
+        assert!(150_000 == fee);
+
+        let balance = SignedAmount::from_sat(-10);
+        assert!(balance < 0);
+        assert!(balance == -10);
+        assert!(0 > balance);
+    }
+
+    #[test]
+    fn amount_int_conversions() {
+        assert_eq!(Amount::from(546u32), Amount::from_sat(546));
+        assert_eq!(Amount::from(546u64), Amount::from_sat(546));
+        assert_eq!(Amount::try_from(546i64), Ok(Amount::from_sat(546)));
+        assert!(Amount::try_from(-1i64).is_err());
+        assert_eq!(Amount::try_from(546i128), Ok(Amount::from_sat(546)));
+        assert!(Amount::try_from(i128::from(u64::MAX) + 1).is_err());
+
+        assert_eq!(u64::from(Amount::from_sat(546)), 546);
+        assert_eq!(i128::from(MAX), i128::from(u64::MAX));
+        assert_eq!(i64::try_from(Amount::from_sat(546)), Ok(546));
+        assert!(i64::try_from(MAX).is_err());
     }
 }
@@ -0,0 +1,155 @@
+//! Compile-time checked amount literals, see `btc!` and `sat!`.
+//!
+//! The macros evaluate the const fns of this module in a `const` item, so an
+//! invalid literal is a compile error rather than a runtime one. They're
+//! public only for the macros to reach them.
+
+use Amount;
+
+/// Creates an `Amount` from a literal number of BTC, e.g. `btc!(0.001)`,
+/// checked at compile time.
+///
+/// The literal must be a decimal number, digits can be grouped with `_`, with
+/// at most 8 significant decimal places and not exceed `Amount::MAX_MONEY`.
+///
+/// ```
+/// # #[macro_use] extern crate bitcoin_amount;
+/// # fn main() {
+/// const FEE: bitcoin_amount::Amount = btc!(0.0001);
+/// assert_eq!(FEE.into_inner(), 10_000);
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate bitcoin_amount;
+/// # fn main() {
+/// let _ = btc!(0.000000001);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! btc {
+    ($btc:literal) => {{
+        const AMOUNT: $crate::Amount = $crate::literal::btc(stringify!($btc));
+        AMOUNT
+    }};
+}
+
+/// Creates an `Amount` from a literal number of satoshis, e.g.
+/// `sat!(100_000)`, checked at compile time not to exceed
+/// `Amount::MAX_MONEY`.
+#[macro_export]
+macro_rules! sat {
+    ($sat:expr) => {{
+        const AMOUNT: $crate::Amount = $crate::literal::sat($sat);
+        AMOUNT
+    }};
+}
+
+/// Parses the text of a `btc!` literal.
+#[doc(hidden)]
+pub const fn btc(s: &str) -> Amount {
+    let bytes = s.as_bytes();
+    let (mut sat, mut digits, mut decimals, mut point) = (0u64, 0, 0, false);
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        i += 1;
+
+        let digit = match b {
+            b'0'..=b'9' => (b - b'0') as u64,
+            b'_' => continue,
+            b'.' if !point => {
+                point = true;
+                continue;
+            }
+            _ => panic!("btc! expects a decimal number literal"),
+        };
+
+        digits += 1;
+        if point && decimals == 8 {
+            if digit != 0 {
+                panic!("btc! literal has more decimal places than a satoshi");
+            }
+            continue;
+        }
+        if point {
+            decimals += 1;
+        }
+
+        sat = push_digit(sat, digit);
+    }
+
+    if digits == 0 {
+        panic!("btc! expects a decimal number literal");
+    }
+    while decimals < 8 {
+        sat = push_digit(sat, 0);
+        decimals += 1;
+    }
+
+    self::sat(sat)
+}
+
+/// Appends `digit` to the satoshis of a `btc!` literal.
+const fn push_digit(sat: u64, digit: u64) -> u64 {
+    let sat = match sat.checked_mul(10) {
+        Some(sat) => sat.checked_add(digit),
+        None => None,
+    };
+    match sat {
+        Some(sat) => sat,
+        None => panic!("btc! literal exceeds MAX_MONEY"),
+    }
+}
+
+/// Checks a `sat!` literal.
+#[doc(hidden)]
+pub const fn sat(sat: u64) -> Amount {
+    if sat > Amount::MAX_MONEY.into_inner() {
+        panic!("amount literal exceeds MAX_MONEY");
+    }
+    Amount::from_sat(sat)
+}
+
+#[cfg(test)]
+mod tests {
+    use Amount;
+
+    #[test]
+    fn literal_macros() {
+        const FEE: Amount = btc!(0.0001);
+        assert_eq!(FEE, Amount::from_sat(10_000));
+        assert_eq!(btc!(1), Amount::from_sat(100_000_000));
+        assert_eq!(btc!(21_000_000.0), Amount::MAX_MONEY);
+        assert_eq!(btc!(0.00000001), Amount::from_sat(1));
+        assert_eq!(btc!(1.000000000), Amount::from_sat(100_000_000));
+        assert_eq!(sat!(100_000), Amount::from_sat(100_000));
+        assert_eq!(sat!(2_100_000_000_000_000), Amount::MAX_MONEY);
+    }
+
+    #[test]
+    #[should_panic(expected = "more decimal places than a satoshi")]
+    fn literal_too_precise() {
+        super::btc("0.000000001");
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds MAX_MONEY")]
+    fn literal_out_of_range() {
+        super::btc("21000000.00000001");
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds MAX_MONEY")]
+    fn literal_overflow() {
+        // The last digit overflows the addition, not the multiplication.
+        super::btc("184467440737.09551619");
+    }
+
+    #[test]
+    #[should_panic(expected = "expects a decimal number literal")]
+    fn literal_exponent() {
+        super::btc("1e3");
+    }
+}
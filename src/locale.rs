@@ -0,0 +1,113 @@
+//! Locale-aware formatting of amounts.
+//!
+//! A small table of the decimal and digit group separators used by common
+//! locales, enough to show amounts the way users of a locale expect without
+//! depending on a full internationalization library.
+//!
+//! Digits are always grouped by thousands, locales grouping them
+//! differently, e.g. the lakh and crore of `en-IN`, aren't supported.
+
+/// The separators used to format numbers in a locale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    decimal: char,
+    group: char,
+}
+
+/// Locale tags, by language or language and region, and their separators.
+/// Regions are looked up before their language.
+static LOCALES: &[(&str, Locale)] = &[
+    ("en", Locale { decimal: '.', group: ',' }),
+    ("de", Locale { decimal: ',', group: '.' }),
+    ("de-ch", Locale { decimal: '.', group: '\u{2019}' }),
+    ("es", Locale { decimal: ',', group: '.' }),
+    ("es-mx", Locale { decimal: '.', group: ',' }),
+    ("fr", Locale { decimal: ',', group: '\u{202f}' }),
+    ("it", Locale { decimal: ',', group: '.' }),
+    ("ja", Locale { decimal: '.', group: ',' }),
+    ("ko", Locale { decimal: '.', group: ',' }),
+    ("nl", Locale { decimal: ',', group: '.' }),
+    ("pl", Locale { decimal: ',', group: '\u{a0}' }),
+    ("pt", Locale { decimal: ',', group: '\u{a0}' }),
+    ("pt-br", Locale { decimal: ',', group: '.' }),
+    ("ru", Locale { decimal: ',', group: '\u{a0}' }),
+    ("sv", Locale { decimal: ',', group: '\u{a0}' }),
+    ("tr", Locale { decimal: ',', group: '.' }),
+    ("zh", Locale { decimal: '.', group: ',' }),
+];
+
+impl Locale {
+    /// The locale used by default, `.` as the decimal separator and `,` to
+    /// group digits.
+    pub const DEFAULT: Locale = Locale { decimal: '.', group: ',' };
+
+    /// Creates a locale from custom separators.
+    pub fn new(decimal: char, group: char) -> Locale {
+        Locale { decimal, group }
+    }
+
+    /// Looks up a locale by its BCP 47 language tag, e.g. `de` or `pt-BR`.
+    /// Falls back to the language if the region is unknown, returns `None`
+    /// if the language is unknown too.
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let language = tag.split(['-', '_']).next().unwrap_or("");
+
+        LOCALES.iter()
+            .find(|&&(t, _)| tag_eq(t, tag))
+            .or_else(|| LOCALES.iter().find(|&&(t, _)| tag_eq(t, language)))
+            .map(|&(_, locale)| locale)
+    }
+
+    /// The decimal separator of this locale.
+    pub fn decimal_separator(self) -> char {
+        self.decimal
+    }
+
+    /// The digit group separator of this locale.
+    pub fn group_separator(self) -> char {
+        self.group
+    }
+}
+
+/// Compares a tag of `LOCALES` to a user given one, case-insensitively and
+/// taking `_` for `-`.
+fn tag_eq(known: &str, tag: &str) -> bool {
+    known.len() == tag.len()
+        && known.bytes().zip(tag.bytes()).all(|(k, t)| {
+            k == t.to_ascii_lowercase() || (k == b'-' && t == b'_')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {Amount, Denomination};
+
+    #[test]
+    fn locale_from_tag() {
+        assert_eq!(Locale::from_tag("de"), Some(Locale::new(',', '.')));
+        assert_eq!(Locale::from_tag("de-AT"), Some(Locale::new(',', '.')));
+        assert_eq!(Locale::from_tag("de_CH"), Some(Locale::new('.', '\u{2019}')));
+        assert_eq!(Locale::from_tag("pt-BR"), Some(Locale::new(',', '.')));
+        assert_eq!(Locale::from_tag("en-US"), Some(Locale::DEFAULT));
+        assert_eq!(Locale::from_tag("xx"), None);
+    }
+
+    #[test]
+    fn locale_display() {
+        let de = Locale::from_tag("de-DE").unwrap();
+        let amt = Amount::from_sat(250_000);
+        assert_eq!(amt.display_in(Denomination::Bitcoin).trim_trailing_zeros(true)
+                      .show_denomination(true).locale(de).to_string(), "0,0025 BTC");
+
+        let amt = Amount::from_sat(123_456_789_000).display_in(Denomination::Bitcoin);
+        assert_eq!(amt.locale(de).to_string(), "1234,56789000");
+        assert_eq!(amt.locale(de).group_digits_locale().to_string(), "1.234,56789000");
+        assert_eq!(amt.group_digits_locale().locale(de).to_string(), "1.234,56789000");
+        assert_eq!(amt.locale(de).group_digits(' ').to_string(), "1 234,56789000");
+        assert_eq!(amt.group_digits(' ').locale(de).to_string(), "1 234,56789000");
+        assert_eq!(amt.locale(de).decimal_separator('.').to_string(), "1234.56789000");
+        assert_eq!(amt.group_digits_locale().to_string(), "1,234.56789000");
+        assert_eq!(Amount::from_sat(2_100).display_human().locale(de).to_string(), "2.100 sat");
+    }
+}
@@ -0,0 +1,594 @@
+//! Internal macros shared by the amount types.
+
+/// Implements the reference variants of a binary operator, like the standard
+/// library does for the primitive integers.
+macro_rules! forward_ref_binop {
+    (impl $imp:ident, $method:ident for $t:ty, $u:ty) => {
+        impl<'a> $imp<$u> for &'a $t {
+            type Output = <$t as $imp<$u>>::Output;
+
+            fn $method(self, rhs: $u) -> Self::Output {
+                $imp::$method(*self, rhs)
+            }
+        }
+
+        impl<'a> $imp<&'a $u> for $t {
+            type Output = <$t as $imp<$u>>::Output;
+
+            fn $method(self, rhs: &'a $u) -> Self::Output {
+                $imp::$method(self, *rhs)
+            }
+        }
+
+        impl<'a, 'b> $imp<&'a $u> for &'b $t {
+            type Output = <$t as $imp<$u>>::Output;
+
+            fn $method(self, rhs: &'a $u) -> Self::Output {
+                $imp::$method(*self, *rhs)
+            }
+        }
+    };
+}
+
+/// Implements the reference variant of a compound assignment operator.
+macro_rules! forward_ref_op_assign {
+    (impl $imp:ident, $method:ident for $t:ty, $u:ty) => {
+        impl<'a> $imp<&'a $u> for $t {
+            fn $method(&mut self, rhs: &'a $u) {
+                $imp::$method(self, *rhs);
+            }
+        }
+    };
+}
+
+/// Implements the constructors, arithmetic methods and operators that are
+/// the same for both `Amount` and `SignedAmount`, `$inner` being the
+/// primitive type holding the satoshis.
+macro_rules! impl_amount {
+    ($ty:ident, $inner:ty) => {
+        impl $ty {
+            /// The maximum amount of money that can ever exist, 21,000,000
+            /// BTC. Amounts out of the `-MAX_MONEY..=MAX_MONEY` range are
+            /// invalid by consensus.
+            pub const MAX_MONEY: $ty = $ty(21_000_000 * 100_000_000);
+
+            /// Creates a new amount from a satoshi amount.
+            pub const fn from_sat(sat: $inner) -> $ty {
+                $ty(sat).check_money()
+            }
+
+            /// Creates a new amount from satoshis read from external input.
+            /// With the `strict-money` feature enabled, satoshis out of the
+            /// valid money range are an error instead of an assertion
+            /// failure, so parsing and conversions never panic on them.
+            pub(crate) const fn try_from_sat(sat: $inner) -> Result<$ty, OutOfRangeError> {
+                if cfg!(feature = "strict-money") && !$ty(sat).is_valid_money() {
+                    Err(OutOfRangeError(()))
+                } else {
+                    Ok($ty(sat))
+                }
+            }
+
+            /// Creates a new amount from a satoshi amount without the
+            /// `strict-money` assertion, for the generators of arbitrary
+            /// amounts, which cover the invalid ones on purpose.
+            #[cfg(any(feature = "arbitrary", feature = "proptest", feature = "rand"))]
+            pub(crate) const fn from_sat_unasserted(sat: $inner) -> $ty {
+                $ty(sat)
+            }
+
+            /// Returns `true` if this amount is within the range of valid
+            /// amounts of money, `-MAX_MONEY..=MAX_MONEY`.
+            pub const fn is_valid_money(self) -> bool {
+                (self.0 as i128).abs() <= $ty::MAX_MONEY.0 as i128
+            }
+
+            /// With the `strict-money` feature enabled asserts, on debug
+            /// builds, that this amount is valid money.
+            const fn check_money(self) -> $ty {
+                #[cfg(feature = "strict-money")]
+                debug_assert!(self.is_valid_money(),
+                              concat!(stringify!($ty), " out of the valid money range"));
+                self
+            }
+
+            /// Returns the additive identity of the amount type.
+            pub const fn zero() -> $ty {
+                $ty(0)
+            }
+
+            /// Returns an amount of one satoshi.
+            #[deprecated(since = "0.1.5",
+                         note = "use `from_sat(1)`, a satoshi isn't a multiplicative identity of amounts")]
+            pub fn one() -> $ty {
+                $ty(1)
+            }
+
+            /// Maximum value that can fit in the amount type.
+            pub const fn max_value() -> $ty {
+                $ty(<$inner>::MAX)
+            }
+
+            /// Minimum value that can fit in the amount type.
+            pub const fn min_value() -> $ty {
+                $ty(<$inner>::MIN)
+            }
+
+            /// Converts this amount to the inner satoshis.
+            pub const fn into_inner(self) -> $inner {
+                self.0
+            }
+
+            /// Converts this amount to a floating point BTC value, which
+            /// loses precision for amounts above 2^53 satoshis.
+            pub fn to_btc(self) -> f64 {
+                self.0 as f64 / ::SAT_PER_BTC_FP
+            }
+
+            /// Converts this amount to a JSON number of BTC with 8 decimal
+            /// places, e.g. `0.00253583`, formatted from the satoshis rather
+            /// than through an `f64` so it's always exact.
+            #[cfg(feature = "serde_json")]
+            pub fn to_serde_json(self) -> ::serde_json::Number {
+                self.to_string().parse().expect("formatted amounts are valid JSON numbers")
+            }
+
+            /// Converts this amount to a `strason` number of BTC with 8
+            /// decimal places, e.g. `0.00253583`, keeping full precision.
+            #[cfg(feature = "strason")]
+            pub fn to_strason_json(self) -> ::strason::Json {
+                ::strason::Json::from_str(&self.to_string())
+                    .expect("formatted amounts are valid JSON numbers")
+            }
+
+            /// Returns `true` if this amount is zero.
+            pub const fn is_zero(self) -> bool {
+                self.0 == 0
+            }
+
+            /// Returns `true` if this amount is greater than zero.
+            pub const fn is_positive(self) -> bool {
+                self.0 > 0
+            }
+
+            /// Returns the smallest of `self` and `other`.
+            pub const fn min(self, other: $ty) -> $ty {
+                if self.0 <= other.0 { self } else { other }
+            }
+
+            /// Returns the largest of `self` and `other`.
+            pub const fn max(self, other: $ty) -> $ty {
+                if self.0 > other.0 { self } else { other }
+            }
+
+            /// Restricts this amount to the `min..=max` interval.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `min > max`.
+            pub const fn clamp(self, min: $ty, max: $ty) -> $ty {
+                assert!(min.0 <= max.0, concat!(stringify!($ty), " clamp with min > max"));
+                self.max(min).min(max)
+            }
+
+            /// Checked addition. Returns `None` if overflow occurred.
+            pub const fn checked_add(self, rhs: $ty) -> Option<$ty> {
+                match self.0.checked_add(rhs.0) {
+                    Some(sat) => Some($ty(sat)),
+                    None => None,
+                }
+            }
+
+            /// Checked subtraction. Returns `None` if overflow occurred.
+            pub const fn checked_sub(self, rhs: $ty) -> Option<$ty> {
+                match self.0.checked_sub(rhs.0) {
+                    Some(sat) => Some($ty(sat)),
+                    None => None,
+                }
+            }
+
+            /// Checked multiplication by a scalar. Returns `None` if overflow
+            /// occurred.
+            pub const fn checked_mul(self, rhs: $inner) -> Option<$ty> {
+                match self.0.checked_mul(rhs) {
+                    Some(sat) => Some($ty(sat)),
+                    None => None,
+                }
+            }
+
+            /// Checked division by a scalar. Returns `None` if `rhs` is zero
+            /// or the division overflowed.
+            pub const fn checked_div(self, rhs: $inner) -> Option<$ty> {
+                match self.0.checked_div(rhs) {
+                    Some(sat) => Some($ty(sat)),
+                    None => None,
+                }
+            }
+
+            /// Checked remainder of the division by a scalar. Returns `None`
+            /// if `rhs` is zero or the division overflowed.
+            pub const fn checked_rem(self, rhs: $inner) -> Option<$ty> {
+                match self.0.checked_rem(rhs) {
+                    Some(sat) => Some($ty(sat)),
+                    None => None,
+                }
+            }
+
+            /// Divides this amount by `divisor`, returning both the quotient
+            /// and the leftover satoshis, so that
+            /// `quotient * divisor + remainder == self`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `divisor` is zero or the division overflowed.
+            pub fn div_rem(self, divisor: $inner) -> ($ty, $ty) {
+                (self / divisor, self % divisor)
+            }
+
+            /// Divides this amount by `divisor`, rounding the quotient towards
+            /// negative infinity.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `divisor` is zero or the division overflowed.
+            pub fn div_floor(self, divisor: $inner) -> $ty {
+                self.div_rounding(divisor, Rounding::Floor)
+            }
+
+            /// Divides this amount by `divisor`, rounding the quotient towards
+            /// positive infinity.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `divisor` is zero or the division overflowed.
+            pub fn div_ceil(self, divisor: $inner) -> $ty {
+                self.div_rounding(divisor, Rounding::Ceil)
+            }
+
+            /// Divides this amount by `divisor`, rounding the quotient to the
+            /// nearest satoshi and half-way cases away from zero.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `divisor` is zero or the division overflowed.
+            pub fn div_round(self, divisor: $inner) -> $ty {
+                self.div_rounding(divisor, Rounding::Nearest)
+            }
+
+            fn div_rounding(self, divisor: $inner, rounding: Rounding) -> $ty {
+                div_rounded(i128::from(self.0), i128::from(divisor), rounding)
+                    .and_then($ty::from_i128)
+                    .expect(concat!(stringify!($ty), " division overflow or division by zero"))
+            }
+
+            /// Converts satoshis computed with a wider integer type back to an
+            /// amount, returns `None` if they don't fit.
+            fn from_i128(sat: i128) -> Option<$ty> {
+                <$inner>::try_from(sat).ok().map($ty)
+            }
+
+            /// Splits this amount into `n` parts whose sum is exactly `self`.
+            ///
+            /// The remainder of the division is distributed one satoshi at a
+            /// time to the first parts, so no part differs from another by
+            /// more than one satoshi.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `n` is zero.
+            #[cfg(feature = "alloc")]
+            pub fn split_evenly(self, n: usize) -> ::alloc::vec::Vec<$ty> {
+                assert!(n != 0, concat!(stringify!($ty), " split into zero parts"));
+
+                let amount = i128::from(self.0);
+                let (quot, rem) = (amount / n as i128, amount % n as i128);
+                let leftover = rem.unsigned_abs() as usize;
+
+                (0..n)
+                    .map(|i| if i < leftover { quot + rem.signum() } else { quot })
+                    .map(|sat| $ty(sat as $inner))
+                    .collect()
+            }
+
+            /// Splits this amount proportionally to `weights`, returning one
+            /// part per weight whose sum is exactly `self`.
+            ///
+            /// Uses the largest remainder method: each part gets its truncated
+            /// share and the leftover satoshis are given one at a time to the
+            /// parts with the largest fractional remainders, ties going to the
+            /// first ones.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `weights` is empty or all of the weights are zero.
+            #[cfg(feature = "alloc")]
+            pub fn split_weighted(self, weights: &[u64]) -> ::alloc::vec::Vec<$ty> {
+                let total = weights.iter().fold(0u128, |acc, &w| acc + u128::from(w));
+                assert!(total != 0, concat!(stringify!($ty), " split with zero total weight"));
+
+                let amount = i128::from(self.0);
+                let magnitude = amount.unsigned_abs();
+                let mut parts = ::alloc::vec::Vec::with_capacity(weights.len());
+                let mut remainders = ::alloc::vec::Vec::with_capacity(weights.len());
+                for (i, &w) in weights.iter().enumerate() {
+                    let share = magnitude * u128::from(w);
+                    parts.push(share / total);
+                    remainders.push((share % total, i));
+                }
+
+                let distributed: u128 = parts.iter().sum();
+                let leftover = (magnitude - distributed) as usize;
+
+                remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+                for &(_, i) in remainders.iter().take(leftover) {
+                    parts[i] += 1;
+                }
+
+                parts.into_iter().map(|sat| $ty((amount.signum() * sat as i128) as $inner)).collect()
+            }
+
+            /// Calculates the middle point of `self` and `other`, rounding
+            /// towards zero, without intermediate overflow.
+            pub fn midpoint(self, other: $ty) -> $ty {
+                $ty(((i128::from(self.0) + i128::from(other.0)) / 2) as $inner)
+            }
+
+            /// Calculates the arithmetic mean of `amounts`, rounding towards
+            /// zero, without intermediate overflow. Returns `None` if
+            /// `amounts` is empty.
+            pub fn mean(amounts: &[$ty]) -> Option<$ty> {
+                if amounts.is_empty() {
+                    return None;
+                }
+
+                let sum = amounts.iter().fold(0i128, |acc, amt| acc + i128::from(amt.0));
+                Some($ty((sum / amounts.len() as i128) as $inner))
+            }
+
+            /// Saturating addition. Computes `self + rhs`, clamping the result
+            /// to `max_value()` or `min_value()` instead of overflowing.
+            pub const fn saturating_add(self, rhs: $ty) -> $ty {
+                $ty(self.0.saturating_add(rhs.0))
+            }
+
+            /// Saturating subtraction. Computes `self - rhs`, clamping the
+            /// result to `max_value()` or `min_value()` instead of
+            /// overflowing.
+            pub const fn saturating_sub(self, rhs: $ty) -> $ty {
+                $ty(self.0.saturating_sub(rhs.0))
+            }
+
+            /// Saturating multiplication by a scalar. Computes `self * rhs`,
+            /// clamping the result to `max_value()` or `min_value()` instead
+            /// of overflowing.
+            pub const fn saturating_mul(self, rhs: $inner) -> $ty {
+                $ty(self.0.saturating_mul(rhs))
+            }
+
+            /// Calculates `self + rhs`. Returns a tuple of the addition along
+            /// with a boolean indicating whether an arithmetic overflow would
+            /// occur. If an overflow would have occurred then the wrapped
+            /// value is returned.
+            pub fn overflowing_add(self, rhs: $ty) -> ($ty, bool) {
+                let (v, o) = self.0.overflowing_add(rhs.0);
+                ($ty(v), o)
+            }
+
+            /// Calculates `self - rhs`. Returns a tuple of the subtraction
+            /// along with a boolean indicating whether an arithmetic overflow
+            /// would occur. If an overflow would have occurred then the
+            /// wrapped value is returned.
+            pub fn overflowing_sub(self, rhs: $ty) -> ($ty, bool) {
+                let (v, o) = self.0.overflowing_sub(rhs.0);
+                ($ty(v), o)
+            }
+
+            /// Calculates `self * rhs` for a scalar `rhs`. Returns a tuple of
+            /// the multiplication along with a boolean indicating whether an
+            /// arithmetic overflow would occur. If an overflow would have
+            /// occurred then the wrapped value is returned.
+            pub fn overflowing_mul(self, rhs: $inner) -> ($ty, bool) {
+                let (v, o) = self.0.overflowing_mul(rhs);
+                ($ty(v), o)
+            }
+
+            /// Wrapping (modular) addition. Computes `self + rhs`, wrapping
+            /// around at the boundary of the type.
+            pub fn wrapping_add(self, rhs: $ty) -> $ty {
+                $ty(self.0.wrapping_add(rhs.0))
+            }
+
+            /// Wrapping (modular) subtraction. Computes `self - rhs`, wrapping
+            /// around at the boundary of the type.
+            pub fn wrapping_sub(self, rhs: $ty) -> $ty {
+                $ty(self.0.wrapping_sub(rhs.0))
+            }
+
+            /// Wrapping (modular) multiplication by a scalar. Computes
+            /// `self * rhs`, wrapping around at the boundary of the type.
+            pub fn wrapping_mul(self, rhs: $inner) -> $ty {
+                $ty(self.0.wrapping_mul(rhs))
+            }
+
+            /// Unchecked addition. Computes `self + rhs`, assuming overflow
+            /// cannot occur.
+            ///
+            /// # Safety
+            ///
+            /// The caller must guarantee that `self + rhs` is within
+            /// `min_value()..=max_value()`, overflowing is undefined behavior.
+            pub unsafe fn unchecked_add(self, rhs: $ty) -> $ty {
+                $ty(self.0.unchecked_add(rhs.0))
+            }
+
+            /// Unchecked subtraction. Computes `self - rhs`, assuming overflow
+            /// cannot occur.
+            ///
+            /// # Safety
+            ///
+            /// The caller must guarantee that `self - rhs` is within
+            /// `min_value()..=max_value()`, overflowing is undefined behavior.
+            pub unsafe fn unchecked_sub(self, rhs: $ty) -> $ty {
+                $ty(self.0.unchecked_sub(rhs.0))
+            }
+
+            /// Unchecked multiplication by a scalar. Computes `self * rhs`,
+            /// assuming overflow cannot occur.
+            ///
+            /// # Safety
+            ///
+            /// The caller must guarantee that `self * rhs` is within
+            /// `min_value()..=max_value()`, overflowing is undefined behavior.
+            pub unsafe fn unchecked_mul(self, rhs: $inner) -> $ty {
+                $ty(self.0.unchecked_mul(rhs))
+            }
+        }
+
+        impl PartialEq<$inner> for $ty {
+            fn eq(&self, other: &$inner) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl PartialEq<$ty> for $inner {
+            fn eq(&self, other: &$ty) -> bool {
+                *self == other.0
+            }
+        }
+
+        impl PartialOrd<$inner> for $ty {
+            fn partial_cmp(&self, other: &$inner) -> Option<Ordering> {
+                self.0.partial_cmp(other)
+            }
+        }
+
+        impl PartialOrd<$ty> for $inner {
+            fn partial_cmp(&self, other: &$ty) -> Option<Ordering> {
+                self.partial_cmp(&other.0)
+            }
+        }
+
+        impl Add for $ty {
+            type Output = $ty;
+
+            fn add(self, rhs: $ty) -> Self::Output {
+                self.checked_add(rhs).expect(concat!(stringify!($ty), " addition overflow"))
+                    .check_money()
+            }
+        }
+
+        forward_ref_binop! { impl Add, add for $ty, $ty }
+
+        impl AddAssign for $ty {
+            fn add_assign(&mut self, rhs: $ty) {
+                *self = *self + rhs;
+            }
+        }
+
+        forward_ref_op_assign! { impl AddAssign, add_assign for $ty, $ty }
+
+        impl Div for $ty {
+            /// The ratio between both amounts, truncated towards zero.
+            type Output = $inner;
+
+            fn div(self, rhs: $ty) -> Self::Output {
+                self.0.checked_div(rhs.0)
+                    .expect(concat!(stringify!($ty), " division overflow or division by zero"))
+            }
+        }
+
+        impl Div<$inner> for $ty {
+            type Output = $ty;
+
+            fn div(self, rhs: $inner) -> Self::Output {
+                self.checked_div(rhs)
+                    .expect(concat!(stringify!($ty), " division overflow or division by zero"))
+            }
+        }
+
+        forward_ref_binop! { impl Div, div for $ty, $inner }
+
+        impl DivAssign<$inner> for $ty {
+            fn div_assign(&mut self, rhs: $inner) {
+                *self = *self / rhs;
+            }
+        }
+
+        forward_ref_op_assign! { impl DivAssign, div_assign for $ty, $inner }
+
+        impl Rem<$inner> for $ty {
+            type Output = $ty;
+
+            fn rem(self, rhs: $inner) -> Self::Output {
+                self.checked_rem(rhs)
+                    .expect(concat!(stringify!($ty), " remainder overflow or division by zero"))
+            }
+        }
+
+        impl Mul<$inner> for $ty {
+            type Output = $ty;
+
+            fn mul(self, rhs: $inner) -> Self::Output {
+                self.checked_mul(rhs).expect(concat!(stringify!($ty), " multiplication overflow"))
+                    .check_money()
+            }
+        }
+
+        forward_ref_binop! { impl Mul, mul for $ty, $inner }
+
+        impl MulAssign<$inner> for $ty {
+            fn mul_assign(&mut self, rhs: $inner) {
+                *self = *self * rhs;
+            }
+        }
+
+        forward_ref_op_assign! { impl MulAssign, mul_assign for $ty, $inner }
+
+        impl Sub for $ty {
+            type Output = $ty;
+
+            fn sub(self, rhs: $ty) -> Self::Output {
+                self.checked_sub(rhs).expect(concat!(stringify!($ty), " subtraction overflow"))
+                    .check_money()
+            }
+        }
+
+        forward_ref_binop! { impl Sub, sub for $ty, $ty }
+
+        impl SubAssign for $ty {
+            fn sub_assign(&mut self, rhs: $ty) {
+                *self = *self - rhs;
+            }
+        }
+
+        forward_ref_op_assign! { impl SubAssign, sub_assign for $ty, $ty }
+
+        impl Sum for $ty {
+            fn sum<I>(iter: I) -> $ty
+            where
+                I: Iterator<Item = $ty>
+            {
+                iter.fold($ty::zero(), Add::add)
+            }
+        }
+
+        impl<'a> Sum<&'a $ty> for $ty {
+            fn sum<I>(iter: I) -> $ty
+            where
+                I: Iterator<Item = &'a $ty>
+            {
+                iter.fold($ty::zero(), Add::add)
+            }
+        }
+
+        impl<I, T> CheckedSum<$ty> for I
+        where
+            I: Iterator<Item = T>,
+            T: Borrow<$ty>,
+        {
+            fn checked_sum(mut self) -> Option<$ty> {
+                self.try_fold($ty::zero(), |acc, amt| acc.checked_add(*amt.borrow()))
+            }
+        }
+    };
+}
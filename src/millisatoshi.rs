@@ -0,0 +1,238 @@
+//! Millisatoshi amounts, the unit of Lightning payments.
+
+use core::convert::TryFrom;
+use core::fmt::{self, Display, Formatter};
+use core::iter::Sum;
+use core::ops::{Add, AddAssign, Div, Mul, Rem, Sub, SubAssign};
+use core::str::FromStr;
+
+use {div_rounded, Amount, OutOfRangeError, ParseAmountError, Rounding};
+
+/// The amount of millisatoshis in a satoshi.
+pub const MSAT_PER_SAT: u64 = 1_000;
+
+/// An amount of millisatoshis, a thousandth of a satoshi.
+///
+/// Lightning payments and channel balances are denominated in
+/// millisatoshis, which can't be represented on-chain. Converting an
+/// `Amount` is exact, converting back rounds to a whole satoshi as chosen
+/// with `to_amount`.
+///
+/// Formats as the integer number of millisatoshis followed by `msat`, e.g.
+/// `253583123 msat`, and parses from the same with the denomination being
+/// optional.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct MilliSatoshi(u64);
+
+impl MilliSatoshi {
+    /// Creates a new amount from a number of millisatoshis.
+    pub fn from_msat(msat: u64) -> MilliSatoshi {
+        MilliSatoshi(msat)
+    }
+
+    /// Returns a zero amount.
+    pub fn zero() -> MilliSatoshi {
+        MilliSatoshi(0)
+    }
+
+    /// Converts this amount to the inner millisatoshis.
+    pub fn into_inner(self) -> u64 {
+        self.0
+    }
+
+    /// Converts this amount to whole satoshis, rounded as specified by
+    /// `rounding`.
+    ///
+    /// Millisatoshis always fit in an `Amount`, this only fails with the
+    /// `strict-money` feature enabled when the satoshis aren't valid money.
+    pub fn to_amount(self, rounding: Rounding) -> Result<Amount, OutOfRangeError> {
+        let sat = div_rounded(i128::from(self.0), i128::from(MSAT_PER_SAT), rounding)
+            .expect("MSAT_PER_SAT isn't zero");
+        Amount::try_from(sat)
+    }
+
+    /// Checked addition. Returns `None` if overflow occurred.
+    pub fn checked_add(self, rhs: MilliSatoshi) -> Option<MilliSatoshi> {
+        self.0.checked_add(rhs.0).map(MilliSatoshi)
+    }
+
+    /// Checked subtraction. Returns `None` if overflow occurred.
+    pub fn checked_sub(self, rhs: MilliSatoshi) -> Option<MilliSatoshi> {
+        self.0.checked_sub(rhs.0).map(MilliSatoshi)
+    }
+
+    /// Checked multiplication by a scalar. Returns `None` if overflow
+    /// occurred.
+    pub fn checked_mul(self, rhs: u64) -> Option<MilliSatoshi> {
+        self.0.checked_mul(rhs).map(MilliSatoshi)
+    }
+
+    /// Checked division by a scalar. Returns `None` if `rhs` is zero.
+    pub fn checked_div(self, rhs: u64) -> Option<MilliSatoshi> {
+        self.0.checked_div(rhs).map(MilliSatoshi)
+    }
+}
+
+/// Converts the satoshis exactly, failing if they don't fit in an `u64` of
+/// millisatoshis, which valid amounts of money always do.
+impl TryFrom<Amount> for MilliSatoshi {
+    type Error = OutOfRangeError;
+
+    fn try_from(amt: Amount) -> Result<MilliSatoshi, OutOfRangeError> {
+        amt.into_inner()
+            .checked_mul(MSAT_PER_SAT)
+            .map(MilliSatoshi)
+            .ok_or(OutOfRangeError(()))
+    }
+}
+
+impl From<u64> for MilliSatoshi {
+    fn from(msat: u64) -> MilliSatoshi {
+        MilliSatoshi(msat)
+    }
+}
+
+impl From<MilliSatoshi> for u64 {
+    fn from(msat: MilliSatoshi) -> u64 {
+        msat.0
+    }
+}
+
+impl Add for MilliSatoshi {
+    type Output = MilliSatoshi;
+
+    fn add(self, rhs: MilliSatoshi) -> Self::Output {
+        self.checked_add(rhs).expect("MilliSatoshi addition overflow")
+    }
+}
+
+forward_ref_binop! { impl Add, add for MilliSatoshi, MilliSatoshi }
+
+impl AddAssign for MilliSatoshi {
+    fn add_assign(&mut self, rhs: MilliSatoshi) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for MilliSatoshi {
+    type Output = MilliSatoshi;
+
+    fn sub(self, rhs: MilliSatoshi) -> Self::Output {
+        self.checked_sub(rhs).expect("MilliSatoshi subtraction overflow")
+    }
+}
+
+forward_ref_binop! { impl Sub, sub for MilliSatoshi, MilliSatoshi }
+
+impl SubAssign for MilliSatoshi {
+    fn sub_assign(&mut self, rhs: MilliSatoshi) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<u64> for MilliSatoshi {
+    type Output = MilliSatoshi;
+
+    fn mul(self, rhs: u64) -> Self::Output {
+        self.checked_mul(rhs).expect("MilliSatoshi multiplication overflow")
+    }
+}
+
+impl Div<u64> for MilliSatoshi {
+    type Output = MilliSatoshi;
+
+    fn div(self, rhs: u64) -> Self::Output {
+        self.checked_div(rhs).expect("MilliSatoshi division by zero")
+    }
+}
+
+impl Rem<u64> for MilliSatoshi {
+    type Output = MilliSatoshi;
+
+    fn rem(self, rhs: u64) -> Self::Output {
+        self.0.checked_rem(rhs).map(MilliSatoshi).expect("MilliSatoshi division by zero")
+    }
+}
+
+impl Sum for MilliSatoshi {
+    fn sum<I>(iter: I) -> MilliSatoshi
+    where
+        I: Iterator<Item = MilliSatoshi>
+    {
+        iter.fold(MilliSatoshi::zero(), Add::add)
+    }
+}
+
+impl Display for MilliSatoshi {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(fmt, "{} msat", self.0)
+    }
+}
+
+/// Parses an integer number of millisatoshis, optionally followed by `msat`.
+impl FromStr for MilliSatoshi {
+    type Err = ParseAmountError;
+
+    fn from_str(s: &str) -> Result<MilliSatoshi, ParseAmountError> {
+        let digits = s.strip_suffix("msat").map_or(s, str::trim_end);
+        if digits.is_empty() {
+            return Err(ParseAmountError::Empty);
+        }
+        if let Some(pos) = digits.bytes().position(|b| !b.is_ascii_digit()) {
+            return Err(ParseAmountError::InvalidCharacter { pos });
+        }
+        digits.parse().map(MilliSatoshi).map_err(|_| ParseAmountError::OutOfRange)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn msat_amount_conversions() {
+        let msat = MilliSatoshi::from_msat(253_583_500);
+        assert_eq!(msat.to_amount(Rounding::Floor), Ok(Amount::from_sat(253_583)));
+        assert_eq!(msat.to_amount(Rounding::Ceil), Ok(Amount::from_sat(253_584)));
+        assert_eq!(msat.to_amount(Rounding::Nearest), Ok(Amount::from_sat(253_584)));
+        assert_eq!(MilliSatoshi::from_msat(1_499).to_amount(Rounding::Nearest), Ok(Amount::from_sat(1)));
+        let max = MilliSatoshi::from_msat(u64::MAX).to_amount(Rounding::Ceil);
+        #[cfg(feature = "strict-money")]
+        assert_eq!(max, Err(OutOfRangeError(())));
+        #[cfg(not(feature = "strict-money"))]
+        assert_eq!(max, Ok(Amount::from_sat(u64::MAX / 1_000 + 1)));
+
+        assert_eq!(MilliSatoshi::try_from(Amount::from_sat(253_583)), Ok(MilliSatoshi::from_msat(253_583_000)));
+        assert_eq!(MilliSatoshi::try_from(Amount::MAX_MONEY).map(MilliSatoshi::into_inner),
+                   Ok(2_100_000_000_000_000_000));
+        assert!(MilliSatoshi::try_from(Amount::max_value()).is_err());
+    }
+
+    #[test]
+    fn msat_arithmetic() {
+        let mut msat = MilliSatoshi::from_msat(1_500);
+        msat += MilliSatoshi::from_msat(500);
+        assert_eq!(msat, MilliSatoshi::from_msat(2_000));
+        msat -= MilliSatoshi::from_msat(1);
+        assert_eq!(msat * 2, MilliSatoshi::from_msat(3_998));
+        assert_eq!(msat / 2, MilliSatoshi::from_msat(999));
+        assert_eq!(msat % 1_000, MilliSatoshi::from_msat(999));
+        assert_eq!(vec![msat, msat].into_iter().sum::<MilliSatoshi>(), MilliSatoshi::from_msat(3_998));
+        assert_eq!(MilliSatoshi::zero().checked_sub(msat), None);
+        assert_eq!(MilliSatoshi::from_msat(u64::MAX).checked_add(msat), None);
+    }
+
+    #[test]
+    fn msat_display_from_str() {
+        let msat = MilliSatoshi::from_msat(253_583_123);
+        assert_eq!(msat.to_string(), "253583123 msat");
+        assert_eq!("253583123 msat".parse(), Ok(msat));
+        assert_eq!("253583123msat".parse(), Ok(msat));
+        assert_eq!("253583123".parse(), Ok(msat));
+
+        assert_eq!("msat".parse::<MilliSatoshi>(), Err(ParseAmountError::Empty));
+        assert_eq!("-1".parse::<MilliSatoshi>(), Err(ParseAmountError::InvalidCharacter { pos: 0 }));
+        assert_eq!("1.5".parse::<MilliSatoshi>(), Err(ParseAmountError::InvalidCharacter { pos: 1 }));
+        assert_eq!("18446744073709551616".parse::<MilliSatoshi>(), Err(ParseAmountError::OutOfRange));
+    }
+}
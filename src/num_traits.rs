@@ -0,0 +1,95 @@
+//! `num-traits` implementations for amounts.
+//!
+//! Only the traits whose operations are closed over amounts are
+//! implemented: adding or subtracting two amounts gives an amount, but
+//! multiplying or dividing two of them doesn't, so `One`, `CheckedMul` and
+//! `CheckedDiv` aren't, see the inherent methods taking a scalar instead.
+
+use num_traits_crate::{Bounded, CheckedAdd, CheckedSub, Saturating, SaturatingAdd, SaturatingSub, Zero};
+
+use {Amount, SignedAmount};
+
+macro_rules! impl_num_traits {
+    ($ty:ident) => {
+        impl Zero for $ty {
+            fn zero() -> $ty {
+                $ty::zero()
+            }
+
+            fn is_zero(&self) -> bool {
+                $ty::is_zero(*self)
+            }
+        }
+
+        impl Bounded for $ty {
+            fn min_value() -> $ty {
+                $ty::min_value()
+            }
+
+            fn max_value() -> $ty {
+                $ty::max_value()
+            }
+        }
+
+        impl CheckedAdd for $ty {
+            fn checked_add(&self, v: &$ty) -> Option<$ty> {
+                $ty::checked_add(*self, *v)
+            }
+        }
+
+        impl CheckedSub for $ty {
+            fn checked_sub(&self, v: &$ty) -> Option<$ty> {
+                $ty::checked_sub(*self, *v)
+            }
+        }
+
+        impl Saturating for $ty {
+            fn saturating_add(self, v: $ty) -> $ty {
+                $ty::saturating_add(self, v)
+            }
+
+            fn saturating_sub(self, v: $ty) -> $ty {
+                $ty::saturating_sub(self, v)
+            }
+        }
+
+        impl SaturatingAdd for $ty {
+            fn saturating_add(&self, v: &$ty) -> $ty {
+                $ty::saturating_add(*self, *v)
+            }
+        }
+
+        impl SaturatingSub for $ty {
+            fn saturating_sub(&self, v: &$ty) -> $ty {
+                $ty::saturating_sub(*self, *v)
+            }
+        }
+    };
+}
+
+impl_num_traits!(Amount);
+impl_num_traits!(SignedAmount);
+
+#[cfg(test)]
+mod tests {
+    use num_traits_crate::{CheckedAdd, SaturatingSub, Zero};
+
+    use {Amount, SignedAmount};
+
+    /// Sums like a generic accumulation library would.
+    fn checked_total<T: Zero + CheckedAdd>(values: &[T]) -> Option<T> {
+        values.iter().try_fold(T::zero(), |acc, v| acc.checked_add(v))
+    }
+
+    #[test]
+    fn num_traits_generic() {
+        let amounts = [Amount::from_sat(1), Amount::from_sat(2)];
+        assert_eq!(checked_total(&amounts), Some(Amount::from_sat(3)));
+        assert_eq!(checked_total::<Amount>(&[]), Some(Amount::zero()));
+        assert!(<SignedAmount as Zero>::zero().is_zero());
+
+        let diff = SaturatingSub::saturating_sub(&Amount::from_sat(1), &Amount::from_sat(2));
+        assert_eq!(diff, Amount::zero());
+        assert_eq!(checked_total(&[Amount::max_value(), Amount::from_sat(1)]), None);
+    }
+}
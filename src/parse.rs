@@ -0,0 +1,891 @@
+//! Exact parsing of decimal amount strings.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt::{self, Display, Formatter};
+#[cfg(feature = "alloc")]
+use core::ops::Range;
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::error;
+
+use {Amount, Denomination, ParseDenominationError, SignedAmount};
+
+/// A configurable amount parser, for contexts needing a different
+/// strictness than `FromStr`, e.g. consensus values vs user entry.
+///
+/// By default numbers are parsed as BTC, a leading `-` or `+` and
+/// scientific notation are accepted, every denomination is allowed, digits
+/// can't be grouped and only sub-satoshi precision is rejected, same as
+/// `FromStr`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AmountParser {
+    denomination: Denomination,
+    denomination_suffix: bool,
+    allowed_denominations: u8,
+    allow_negative: bool,
+    allow_plus: bool,
+    max_decimals: Option<usize>,
+    trim_whitespace: bool,
+    group_separator: Option<char>,
+    allow_exponent: bool,
+}
+
+impl AmountParser {
+    /// Creates a parser with the default options.
+    pub fn new() -> AmountParser {
+        AmountParser {
+            denomination: Denomination::Bitcoin,
+            denomination_suffix: false,
+            allowed_denominations: !0,
+            allow_negative: true,
+            allow_plus: true,
+            max_decimals: None,
+            trim_whitespace: false,
+            group_separator: None,
+            allow_exponent: true,
+        }
+    }
+
+    /// Sets the denomination of the numbers, BTC by default.
+    pub fn denomination(mut self, denom: Denomination) -> AmountParser {
+        self.denomination = denom;
+        self
+    }
+
+    /// Sets whether the number must be followed by its denomination, e.g.
+    /// `1.5 mBTC`, instead of being in the denomination set by
+    /// `denomination`.
+    pub fn denomination_suffix(mut self, suffix: bool) -> AmountParser {
+        self.denomination_suffix = suffix;
+        self
+    }
+
+    /// Restricts the denominations amounts can be in to `denoms`.
+    pub fn allowed_denominations(mut self, denoms: &[Denomination]) -> AmountParser {
+        self.allowed_denominations = denoms.iter().fold(0, |mask, &denom| mask | 1 << denom as u8);
+        self
+    }
+
+    /// Sets whether negative amounts are accepted. When they aren't, parsing
+    /// a negative `SignedAmount` fails as for an `Amount`.
+    pub fn allow_negative(mut self, allow: bool) -> AmountParser {
+        self.allow_negative = allow;
+        self
+    }
+
+    /// Sets whether a leading `+` is accepted.
+    pub fn allow_plus(mut self, allow: bool) -> AmountParser {
+        self.allow_plus = allow;
+        self
+    }
+
+    /// Limits the number of decimal places, any digit past the limit is
+    /// rejected even if it's a zero.
+    pub fn max_decimals(mut self, max: usize) -> AmountParser {
+        self.max_decimals = Some(max);
+        self
+    }
+
+    /// Sets whether leading and trailing ASCII whitespace is ignored.
+    pub fn trim_whitespace(mut self, trim: bool) -> AmountParser {
+        self.trim_whitespace = trim;
+        self
+    }
+
+    /// Accepts `separator` between digits to group them, e.g. `_` for
+    /// `1_000_000` or `,` for `1,000,000`. The separator can't be `.`, which
+    /// is always the decimal point.
+    pub fn group_separator(mut self, separator: char) -> AmountParser {
+        self.group_separator = Some(separator);
+        self
+    }
+
+    /// Sets whether scientific notation, e.g. `2.5e-3`, is accepted. The
+    /// exponent shifts the decimal point exactly, without going through
+    /// floating point.
+    pub fn allow_exponent(mut self, allow: bool) -> AmountParser {
+        self.allow_exponent = allow;
+        self
+    }
+
+    /// Parses `s` as an `Amount`.
+    pub fn parse_amount(&self, s: &str) -> Result<Amount, ParseAmountError> {
+        self.parse_amount_bytes(s.as_bytes())
+    }
+
+    /// Parses `s` as a `SignedAmount`.
+    pub fn parse_signed_amount(&self, s: &str) -> Result<SignedAmount, ParseAmountError> {
+        self.parse_signed_amount_bytes(s.as_bytes())
+    }
+
+    /// Parses the `ranges` of `input`, e.g. the fields of a CSV column, as
+    /// amounts.
+    ///
+    /// Returns the amounts, in the order of `ranges`, along with the index
+    /// and error of the ranges that failed to parse, which are left as zero
+    /// in the amounts. Ranges are parsed as bytes, in place, so `input`
+    /// doesn't need to be valid UTF-8 outside of them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a range is out of the bounds of `input`.
+    #[cfg(feature = "alloc")]
+    pub fn parse_amounts(&self, input: &[u8], ranges: &[Range<usize>])
+        -> (Vec<Amount>, Vec<(usize, ParseAmountError)>)
+    {
+        self.parse_batch(input, ranges, |s| self.parse_amount_bytes(s))
+    }
+
+    /// Parses the `ranges` of `input` as signed amounts, see
+    /// `parse_amounts`.
+    #[cfg(feature = "alloc")]
+    pub fn parse_signed_amounts(&self, input: &[u8], ranges: &[Range<usize>])
+        -> (Vec<SignedAmount>, Vec<(usize, ParseAmountError)>)
+    {
+        self.parse_batch(input, ranges, |s| self.parse_signed_amount_bytes(s))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn parse_batch<T, F>(&self, input: &[u8], ranges: &[Range<usize>], parse: F)
+        -> (Vec<T>, Vec<(usize, ParseAmountError)>)
+    where
+        T: Default,
+        F: Fn(&[u8]) -> Result<T, ParseAmountError>,
+    {
+        let mut amounts = Vec::with_capacity(ranges.len());
+        let mut errors = Vec::new();
+        for (i, range) in ranges.iter().enumerate() {
+            match parse(&input[range.clone()]) {
+                Ok(amount) => amounts.push(amount),
+                Err(e) => {
+                    amounts.push(T::default());
+                    errors.push((i, e));
+                }
+            }
+        }
+
+        (amounts, errors)
+    }
+
+    fn parse_amount_bytes(&self, s: &[u8]) -> Result<Amount, ParseAmountError> {
+        match self.parse(s)? {
+            (true, sat) if sat != 0 => Err(ParseAmountError::Negative),
+            (_, sat) => Amount::try_from_sat(sat).map_err(|_| ParseAmountError::OutOfRange),
+        }
+    }
+
+    fn parse_signed_amount_bytes(&self, s: &[u8]) -> Result<SignedAmount, ParseAmountError> {
+        let (negative, sat) = self.parse(s)?;
+        let sat = if negative { -i128::from(sat) } else { i128::from(sat) };
+
+        i64::try_from(sat)
+            .ok()
+            .and_then(|sat| SignedAmount::try_from_sat(sat).ok())
+            .ok_or(ParseAmountError::OutOfRange)
+    }
+
+    /// Parses the UTF-8 bytes `s` into its sign and its magnitude in
+    /// satoshis. The grammar is ASCII but for the `µ` of `µBTC` and the
+    /// group separator, so the bytes are parsed as they are.
+    fn parse(&self, s: &[u8]) -> Result<(bool, u64), ParseAmountError> {
+        let (s, offset) = if self.trim_whitespace {
+            let trimmed = s.trim_ascii_start();
+            (trimmed.trim_ascii_end(), s.len() - trimmed.len())
+        } else {
+            (s, 0)
+        };
+
+        let (number, denom) = if self.denomination_suffix {
+            // An `e` followed by a digit or a sign is an exponent, not the
+            // start of the denomination.
+            let split = (0..s.len())
+                .find(|&i| {
+                    let exponent = (s[i] == b'e' || s[i] == b'E') &&
+                        s.get(i + 1).is_some_and(|&b| b.is_ascii_digit() || b == b'-' || b == b'+');
+                    (s[i].is_ascii_alphabetic() && !exponent) || s[i..].starts_with("µ".as_bytes())
+                })
+                .unwrap_or(s.len());
+            let denom = Denomination::from_bytes(&s[split..])
+                .map_err(ParseAmountError::UnknownDenomination)?;
+            (s[..split].trim_ascii_end(), denom)
+        } else {
+            (s, self.denomination)
+        };
+
+        if self.allowed_denominations & 1 << denom as u8 == 0 {
+            return Err(ParseAmountError::DisallowedDenomination(denom));
+        }
+
+        let (negative, sat) = self.parse_number(number, denom).map_err(|e| match e {
+            ParseAmountError::InvalidCharacter { pos } => ParseAmountError::InvalidCharacter { pos: pos + offset },
+            e => e,
+        })?;
+
+        if negative && sat != 0 && !self.allow_negative {
+            return Err(ParseAmountError::Negative);
+        }
+
+        Ok((negative, sat))
+    }
+
+    /// Parses a decimal number of `denom` units, e.g. `-0.00253583` BTC or
+    /// `-2.53583e-3` BTC, into its sign and its magnitude in satoshis.
+    ///
+    /// The digits are accumulated as integers and the exponent only shifts
+    /// the decimal point, so every string without sub-satoshi precision maps
+    /// to its exact satoshi value. Trailing zeros past the last satoshi digit
+    /// are accepted.
+    fn parse_number(&self, s: &[u8], denom: Denomination) -> Result<(bool, u64), ParseAmountError> {
+        let (negative, start) = match s.first() {
+            Some(&b'-') => (true, 1),
+            Some(&b'+') if self.allow_plus => (false, 1),
+            _ => (false, 0),
+        };
+        let mut separator = [0; 4];
+        let separator = self.group_separator.map(|c| c.encode_utf8(&mut separator).as_bytes());
+
+        // The significant digits are accumulated without their trailing
+        // zeros, which are counted apart so that long zero paddings don't
+        // overflow.
+        let mut mantissa = 0u128;
+        let mut zeros = 0u32;
+        let mut decimals = None;
+        let mut exponent = None;
+        let mut has_digits = false;
+        let mut prev_digit = false;
+        let mut pos = start;
+        while let Some(&b) = s.get(pos) {
+            let digit = match b {
+                b'0'..=b'9' => u128::from(b - b'0'),
+                b'.' if decimals.is_none() => {
+                    decimals = Some(0usize);
+                    prev_digit = false;
+                    pos += 1;
+                    continue;
+                }
+                b'e' | b'E' if has_digits && self.allow_exponent => {
+                    exponent = Some(parse_exponent(s, pos)?);
+                    break;
+                }
+                // Group separators are only accepted between two digits.
+                _ => match separator {
+                    Some(separator) if prev_digit && s[pos..].starts_with(separator) &&
+                        s.get(pos + separator.len()).is_some_and(u8::is_ascii_digit) => {
+                        prev_digit = false;
+                        pos += separator.len();
+                        continue;
+                    }
+                    _ => return Err(ParseAmountError::InvalidCharacter { pos }),
+                },
+            };
+            pos += 1;
+            has_digits = true;
+            prev_digit = true;
+
+            if let Some(ref mut n) = decimals {
+                *n += 1;
+            }
+
+            if digit == 0 {
+                zeros += 1;
+            } else {
+                mantissa = 10u128.checked_pow(zeros + 1)
+                    .and_then(|shift| mantissa.checked_mul(shift))
+                    .map(|mantissa| mantissa + digit)
+                    .ok_or(if decimals.is_some() {
+                        ParseAmountError::TooPrecise
+                    } else {
+                        ParseAmountError::OutOfRange
+                    })?;
+                zeros = 0;
+            }
+        }
+
+        if !has_digits {
+            return Err(ParseAmountError::Empty);
+        }
+
+        let exponent = exponent.unwrap_or(0);
+        let decimals = decimals.unwrap_or(0) as i64;
+        if let Some(max) = self.max_decimals {
+            if decimals - exponent > max as i64 {
+                return Err(ParseAmountError::TooPrecise);
+            }
+        }
+
+        // The number is `mantissa * 10^(zeros + exponent - decimals)` units,
+        // with `3 - precision` decimal places per unit in millisatoshis.
+        let shift = i64::from(zeros) + exponent - decimals + i64::from(3 - denom.precision());
+        let msat = if mantissa == 0 {
+            0
+        } else if shift >= 0 {
+            u32::try_from(shift).ok()
+                .and_then(|shift| 10u128.checked_pow(shift))
+                .and_then(|shift| mantissa.checked_mul(shift))
+                .ok_or(ParseAmountError::OutOfRange)?
+        } else {
+            let divisor = u32::try_from(-shift).ok()
+                .and_then(|shift| 10u128.checked_pow(shift))
+                .ok_or(ParseAmountError::TooPrecise)?;
+            if !mantissa.is_multiple_of(divisor) {
+                return Err(ParseAmountError::TooPrecise);
+            }
+            mantissa / divisor
+        };
+
+        if !msat.is_multiple_of(1_000) {
+            return Err(ParseAmountError::TooPrecise);
+        }
+
+        u64::try_from(msat / 1_000)
+            .map(|sat| (negative, sat))
+            .map_err(|_| ParseAmountError::OutOfRange)
+    }
+}
+
+/// Parses the exponent of a number in scientific notation, `e` being at the
+/// byte offset `pos` of `s`. Exponents are saturated at a magnitude large
+/// enough to make any amount out of range or too precise.
+fn parse_exponent(s: &[u8], pos: usize) -> Result<i64, ParseAmountError> {
+    let digits = &s[pos + 1..];
+    let (negative, digits, start) = match digits.first() {
+        Some(&b'-') => (true, &digits[1..], pos + 2),
+        Some(&b'+') => (false, &digits[1..], pos + 2),
+        _ => (false, digits, pos + 1),
+    };
+
+    if digits.is_empty() {
+        return Err(ParseAmountError::InvalidCharacter { pos });
+    }
+
+    let mut exponent = 0i64;
+    for (i, &c) in digits.iter().enumerate() {
+        match c {
+            b'0'..=b'9' => exponent = (exponent * 10 + i64::from(c - b'0')).min(1_000),
+            _ => return Err(ParseAmountError::InvalidCharacter { pos: start + i }),
+        }
+    }
+
+    Ok(if negative { -exponent } else { exponent })
+}
+
+impl Default for AmountParser {
+    fn default() -> AmountParser {
+        AmountParser::new()
+    }
+}
+
+/// The parser of integer satoshi strings.
+fn sat_parser() -> AmountParser {
+    AmountParser::new()
+        .denomination(Denomination::Satoshi)
+        .allow_plus(false)
+        .allow_exponent(false)
+        .max_decimals(0)
+}
+
+impl Amount {
+    /// Parses a decimal number of `denom` units, e.g. `1234.5` mBTC, for
+    /// when the denomination is known out-of-band. Sub-satoshi precision is
+    /// rejected.
+    pub fn from_str_in(s: &str, denom: Denomination) -> Result<Amount, ParseAmountError> {
+        AmountParser::new().denomination(denom).parse_amount(s)
+    }
+
+    /// Parses an integer number of satoshis, e.g. `253583`, as many APIs
+    /// transmit amounts in satoshis as strings.
+    pub fn from_sat_str(s: &str) -> Result<Amount, ParseAmountError> {
+        sat_parser().parse_amount(s)
+    }
+
+    /// Parses an amount followed by its denomination, e.g. `1.5 mBTC`,
+    /// `2_100 sat` or `0.5BTC`. The denomination is matched
+    /// case-insensitively, see `Denomination`'s `FromStr`, digits can be
+    /// grouped with `_`.
+    pub fn from_str_with_denomination(s: &str) -> Result<Amount, ParseAmountError> {
+        AmountParser::new().denomination_suffix(true).group_separator('_').parse_amount(s)
+    }
+
+    /// Parses a `serde_json` number of BTC, e.g. `0.00253583` in a bitcoind
+    /// RPC response, exactly from its decimal text. Negative numbers and
+    /// sub-satoshi precision are rejected.
+    #[cfg(feature = "serde_json")]
+    pub fn from_serde_json(num: &::serde_json::Number) -> Result<Amount, ParseAmountError> {
+        Amount::from_str(num.as_str())
+    }
+
+    /// Parses a `strason` number of BTC exactly from its decimal text, same
+    /// as `from_serde_json`. Values other than numbers fail with
+    /// `ParseAmountError::NotANumber`.
+    #[cfg(feature = "strason")]
+    pub fn from_strason_json(json: &::strason::Json) -> Result<Amount, ParseAmountError> {
+        json.num().ok_or(ParseAmountError::NotANumber).and_then(Amount::from_str)
+    }
+}
+
+impl SignedAmount {
+    /// Parses a decimal number of `denom` units, e.g. `-1234.5` mBTC, for
+    /// when the denomination is known out-of-band. Sub-satoshi precision is
+    /// rejected.
+    pub fn from_str_in(s: &str, denom: Denomination) -> Result<SignedAmount, ParseAmountError> {
+        AmountParser::new().denomination(denom).parse_signed_amount(s)
+    }
+
+    /// Parses an integer number of satoshis, e.g. `-253583`, as many APIs
+    /// transmit amounts in satoshis as strings.
+    pub fn from_sat_str(s: &str) -> Result<SignedAmount, ParseAmountError> {
+        sat_parser().parse_signed_amount(s)
+    }
+
+    /// Parses an amount followed by its denomination, e.g. `-1.5 mBTC`,
+    /// `2_100 sat` or `0.5BTC`. The denomination is matched
+    /// case-insensitively, see `Denomination`'s `FromStr`, digits can be
+    /// grouped with `_`.
+    pub fn from_str_with_denomination(s: &str) -> Result<SignedAmount, ParseAmountError> {
+        AmountParser::new().denomination_suffix(true).group_separator('_').parse_signed_amount(s)
+    }
+
+    /// Parses a `serde_json` number of BTC, e.g. `-0.00253583` in a bitcoind
+    /// RPC response, exactly from its decimal text. Sub-satoshi precision is
+    /// rejected.
+    #[cfg(feature = "serde_json")]
+    pub fn from_serde_json(num: &::serde_json::Number) -> Result<SignedAmount, ParseAmountError> {
+        SignedAmount::from_str(num.as_str())
+    }
+
+    /// Parses a `strason` number of BTC exactly from its decimal text, same
+    /// as `from_serde_json`. Values other than numbers fail with
+    /// `ParseAmountError::NotANumber`.
+    #[cfg(feature = "strason")]
+    pub fn from_strason_json(json: &::strason::Json) -> Result<SignedAmount, ParseAmountError> {
+        json.num().ok_or(ParseAmountError::NotANumber).and_then(SignedAmount::from_str)
+    }
+}
+
+macro_rules! impl_try_from_json {
+    ($ty:ident) => {
+        /// Converts a `serde_json` integer number of satoshis or a string of
+        /// them, e.g. `253583` or `"253583"`, the values of Esplora and
+        /// Electrum APIs. Unlike `from_serde_json` numbers aren't BTC.
+        #[cfg(feature = "serde_json")]
+        impl<'a> TryFrom<&'a ::serde_json::Value> for $ty {
+            type Error = ParseAmountError;
+
+            fn try_from(value: &'a ::serde_json::Value) -> Result<$ty, ParseAmountError> {
+                match *value {
+                    ::serde_json::Value::Number(ref num) => $ty::from_sat_str(num.as_str()),
+                    ::serde_json::Value::String(ref s) => $ty::from_sat_str(s),
+                    _ => Err(ParseAmountError::NotANumber),
+                }
+            }
+        }
+
+        /// Converts a `serde_json` integer number of satoshis or a string of
+        /// them, e.g. `253583` or `"253583"`.
+        #[cfg(feature = "serde_json")]
+        impl TryFrom<::serde_json::Value> for $ty {
+            type Error = ParseAmountError;
+
+            fn try_from(value: ::serde_json::Value) -> Result<$ty, ParseAmountError> {
+                $ty::try_from(&value)
+            }
+        }
+    };
+}
+
+impl_try_from_json!(Amount);
+impl_try_from_json!(SignedAmount);
+
+/// Parses a decimal BTC string exactly, without going through floating
+/// point. A sign is accepted.
+///
+/// Strings with a non-zero digit past the 8th decimal place, e.g.
+/// `0.123456789`, fail with `ParseAmountError::TooPrecise` instead of being
+/// rounded to the nearest satoshi.
+impl FromStr for Amount {
+    type Err = ParseAmountError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match parse_plain_btc(s) {
+            Some((false, sat)) => Amount::try_from_sat(sat).map_err(|_| ParseAmountError::OutOfRange),
+            _ => Amount::from_str_in(s, Denomination::Bitcoin),
+        }
+    }
+}
+
+/// Parses a decimal BTC string exactly, without going through floating
+/// point.
+///
+/// Strings with a non-zero digit past the 8th decimal place, e.g.
+/// `-0.123456789`, fail with `ParseAmountError::TooPrecise` instead of being
+/// rounded to the nearest satoshi.
+impl FromStr for SignedAmount {
+    type Err = ParseAmountError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match parse_plain_btc(s) {
+            Some((negative, sat)) if sat <= i64::MAX as u64 => {
+                let sat = if negative { -(sat as i64) } else { sat as i64 };
+                SignedAmount::try_from_sat(sat).map_err(|_| ParseAmountError::OutOfRange)
+            }
+            _ => SignedAmount::from_str_in(s, Denomination::Bitcoin),
+        }
+    }
+}
+
+/// Parses the common case of `FromStr`, a BTC string made of an optional
+/// `-`, digits and up to 8 decimals, in a single pass over the bytes.
+///
+/// Returns `None` for anything else, including every invalid string, which
+/// is left to `AmountParser` so the errors stay the same.
+fn parse_plain_btc(s: &str) -> Option<(bool, u64)> {
+    let (negative, s) = match s.as_bytes().split_first() {
+        Some((&b'-', rest)) => (true, rest),
+        _ => (false, s.as_bytes()),
+    };
+    let (int, frac) = match s.iter().position(|&b| b == b'.') {
+        Some(dot) => (&s[..dot], &s[dot + 1..]),
+        None => (s, &[][..]),
+    };
+    // 11 integer digits and 8 decimals always fit in a `u64`.
+    if (int.is_empty() && frac.is_empty()) || int.len() > 11 || frac.len() > 8 {
+        return None;
+    }
+
+    let mut sat = 0u64;
+    for &b in int.iter().chain(frac) {
+        if !b.is_ascii_digit() {
+            return None;
+        }
+        sat = sat * 10 + u64::from(b - b'0');
+    }
+    Some((negative, sat * 10u64.pow(8 - frac.len() as u32)))
+}
+
+/// An error during `Amount` parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseAmountError {
+    /// The string has no digits.
+    Empty,
+    /// The string has a character that isn't part of a decimal number at the
+    /// byte offset `pos`.
+    InvalidCharacter {
+        /// The byte offset of the character.
+        pos: usize,
+    },
+    /// The amount has more decimal places than a satoshi.
+    TooPrecise,
+    /// The amount doesn't fit in the amount type.
+    OutOfRange,
+    /// The amount is negative but the amount type is unsigned.
+    Negative,
+    /// The denomination suffix is missing or unknown.
+    UnknownDenomination(ParseDenominationError),
+    /// The denomination isn't allowed by the `AmountParser`.
+    DisallowedDenomination(Denomination),
+    /// The JSON, BSON or `NUMERIC` value being converted isn't a number.
+    NotANumber,
+}
+
+impl Display for ParseAmountError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match *self {
+            ParseAmountError::Empty => write!(fmt, "empty amount"),
+            ParseAmountError::InvalidCharacter { pos } => write!(fmt, "invalid character at position {}", pos),
+            ParseAmountError::TooPrecise => write!(fmt, "amount has more than 8 decimal places"),
+            ParseAmountError::OutOfRange => write!(fmt, "amount out of range"),
+            ParseAmountError::Negative => write!(fmt, "unexpected negative amount"),
+            ParseAmountError::UnknownDenomination(ref e) => write!(fmt, "{}", e),
+            ParseAmountError::DisallowedDenomination(denom) => write!(fmt, "denomination not allowed: {}", denom),
+            ParseAmountError::NotANumber => write!(fmt, "value is not a number"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for ParseAmountError {
+    fn description(&self) -> &'static str {
+        match *self {
+            ParseAmountError::Empty => "empty amount",
+            ParseAmountError::InvalidCharacter { .. } => "invalid character",
+            ParseAmountError::TooPrecise => "amount too precise",
+            ParseAmountError::OutOfRange => "amount out of range",
+            ParseAmountError::Negative => "unexpected negative amount",
+            ParseAmountError::UnknownDenomination(_) => "unknown denomination",
+            ParseAmountError::DisallowedDenomination(_) => "denomination not allowed",
+            ParseAmountError::NotANumber => "not a number",
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match *self {
+            ParseAmountError::UnknownDenomination(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_decimal(s: &str, denom: Denomination) -> Result<(bool, u64), ParseAmountError> {
+        AmountParser::new().denomination(denom).parse(s.as_bytes())
+    }
+
+    #[test]
+    fn parse_btc_exact() {
+        assert_eq!(parse_decimal("0.00253583", Denomination::Bitcoin), Ok((false, 253583)));
+        assert_eq!(parse_decimal("-0.1", Denomination::Bitcoin), Ok((true, 10_000_000)));
+        assert_eq!(parse_decimal("+21000000", Denomination::Bitcoin), Ok((false, 2_100_000_000_000_000)));
+        assert_eq!(parse_decimal("1.", Denomination::Bitcoin), Ok((false, 100_000_000)));
+        assert_eq!(parse_decimal(".5", Denomination::Bitcoin), Ok((false, 50_000_000)));
+        assert_eq!(parse_decimal("0.100000000000", Denomination::Bitcoin), Ok((false, 10_000_000)));
+        // 0.29 and 20000000.00000001 aren't representable as a f64.
+        assert_eq!(parse_decimal("0.29", Denomination::Bitcoin), Ok((false, 29_000_000)));
+        assert_eq!(parse_decimal("20000000.00000001", Denomination::Bitcoin), Ok((false, 2_000_000_000_000_001)));
+        assert_eq!(parse_decimal("184467440737.09551615", Denomination::Bitcoin), Ok((false, u64::MAX)));
+    }
+
+    #[test]
+    fn parse_btc_errors() {
+        assert_eq!(parse_decimal("", Denomination::Bitcoin), Err(ParseAmountError::Empty));
+        assert_eq!(parse_decimal("-", Denomination::Bitcoin), Err(ParseAmountError::Empty));
+        assert_eq!(parse_decimal(".", Denomination::Bitcoin), Err(ParseAmountError::Empty));
+        assert_eq!(parse_decimal("1.2.3", Denomination::Bitcoin), Err(ParseAmountError::InvalidCharacter { pos: 3 }));
+        assert_eq!(parse_decimal(" 1", Denomination::Bitcoin), Err(ParseAmountError::InvalidCharacter { pos: 0 }));
+        assert_eq!(parse_decimal("--1", Denomination::Bitcoin), Err(ParseAmountError::InvalidCharacter { pos: 1 }));
+        assert_eq!(parse_decimal("0,5", Denomination::Bitcoin), Err(ParseAmountError::InvalidCharacter { pos: 1 }));
+        assert_eq!(parse_decimal("NaN", Denomination::Bitcoin), Err(ParseAmountError::InvalidCharacter { pos: 0 }));
+        assert_eq!(parse_decimal("0.123456789", Denomination::Bitcoin), Err(ParseAmountError::TooPrecise));
+        assert_eq!(parse_decimal("184467440737.09551616", Denomination::Bitcoin), Err(ParseAmountError::OutOfRange));
+    }
+
+    #[test]
+    fn parse_decimal_denominations() {
+        assert_eq!(parse_decimal("1234.5", Denomination::MilliBitcoin), Ok((false, 123_450_000)));
+        assert_eq!(parse_decimal("2100", Denomination::Satoshi), Ok((false, 2100)));
+        assert_eq!(parse_decimal("2100.0", Denomination::Satoshi), Ok((false, 2100)));
+        assert_eq!(parse_decimal("0.5", Denomination::Satoshi), Err(ParseAmountError::TooPrecise));
+        assert_eq!(parse_decimal("-1.01", Denomination::Bit), Ok((true, 101)));
+        assert_eq!(parse_decimal("5000", Denomination::MilliSatoshi), Ok((false, 5)));
+        assert_eq!(parse_decimal("5001", Denomination::MilliSatoshi), Err(ParseAmountError::TooPrecise));
+        assert_eq!(parse_decimal("18446744073709551615000", Denomination::MilliSatoshi),
+                   Ok((false, u64::MAX)));
+        assert_eq!(parse_decimal("18446744073709551616", Denomination::Satoshi),
+                   Err(ParseAmountError::OutOfRange));
+        assert_eq!(parse_decimal("99999999999999999999999999999999999999999", Denomination::MilliSatoshi),
+                   Err(ParseAmountError::OutOfRange));
+    }
+
+    #[test]
+    fn parse_with_denominations() {
+        assert_eq!(Amount::from_str_with_denomination("1.5 mBTC"), Ok(Amount::from_sat(150_000)));
+        assert_eq!(Amount::from_str_with_denomination("2100 sat"), Ok(Amount::from_sat(2100)));
+        assert_eq!(Amount::from_str_with_denomination("0.5BTC"), Ok(Amount::from_sat(50_000_000)));
+        assert_eq!(Amount::from_str_with_denomination("0.5 btc"), Ok(Amount::from_sat(50_000_000)));
+        assert_eq!(Amount::from_str_with_denomination("3 µBTC"), Ok(Amount::from_sat(300)));
+        assert_eq!(Amount::from_str_with_denomination("-1 sat"), Err(ParseAmountError::Negative));
+        assert_eq!(SignedAmount::from_str_with_denomination("-1 sat"), Ok(SignedAmount::from_sat(-1)));
+        assert_eq!(Amount::from_str_with_denomination("1 sat 2"),
+                   Err(ParseAmountError::UnknownDenomination(Denomination::from_str("sat 2").unwrap_err())));
+        assert!(Amount::from_str_with_denomination("1.5").is_err());
+        assert!(Amount::from_str_with_denomination("1.5 MBTC").is_err());
+        assert_eq!(Amount::from_str_with_denomination("1.5. BTC"),
+                   Err(ParseAmountError::InvalidCharacter { pos: 3 }));
+
+        assert_eq!(Amount::from_str_with_denomination("1e3 sat"), Ok(Amount::from_sat(1_000)));
+        assert_eq!(Amount::from_str_with_denomination("1E3sat"), Ok(Amount::from_sat(1_000)));
+        assert_eq!(Amount::from_str_with_denomination("1e-8 BTC"), Ok(Amount::from_sat(1)));
+        assert_eq!(Amount::from_str_with_denomination("2.5e+2 bits"), Ok(Amount::from_sat(25_000)));
+        // Without a denomination the exponent isn't taken for one.
+        let missing = ParseAmountError::UnknownDenomination(Denomination::from_str("").unwrap_err());
+        assert_eq!(Amount::from_str_with_denomination("1e3"), Err(missing.clone()));
+        assert_eq!(Amount::from_str_with_denomination("1E-3"), Err(missing));
+        assert_eq!(Amount::from_str_with_denomination("1 eth"),
+                   Err(ParseAmountError::UnknownDenomination(Denomination::from_str("eth").unwrap_err())));
+    }
+
+    #[test]
+    fn from_str_plain_matches_parser() {
+        let parser = AmountParser::new();
+        for s in ["0", "-0", "1.", ".5", "-.5", "0.00000001", "-1.23456789", "21000000",
+                  "1.234567891", "1.2345678900", "", "-",
+                  ".", "-.", "1..2", "1.-2", "+1", "1e3", " 1", "1_000"].iter() {
+            assert_eq!(Amount::from_str(s), parser.parse_amount(s), "{}", s);
+            assert_eq!(SignedAmount::from_str(s), parser.parse_signed_amount(s), "{}", s);
+        }
+        for s in ["99999999999.99999999", "-99999999999", "100000000000"].iter() {
+            assert_eq!(Amount::from_str(s), parser.parse_amount(s), "{}", s);
+            assert_eq!(SignedAmount::from_str(s), parser.parse_signed_amount(s), "{}", s);
+        }
+    }
+
+    #[test]
+    fn from_str_in_denomination() {
+        assert_eq!(Amount::from_str_in("1234.5", Denomination::MilliBitcoin),
+                   Ok(Amount::from_sat(123_450_000)));
+        assert_eq!(Amount::from_str_in("1.234", Denomination::Bit), Err(ParseAmountError::TooPrecise));
+        assert_eq!(Amount::from_str_in("2000", Denomination::MilliSatoshi), Ok(Amount::from_sat(2)));
+        assert_eq!(Amount::from_str_in("1 sat", Denomination::Satoshi),
+                   Err(ParseAmountError::InvalidCharacter { pos: 1 }));
+        assert_eq!(SignedAmount::from_str_in("-0.5", Denomination::MicroBitcoin),
+                   Ok(SignedAmount::from_sat(-50)));
+        assert_eq!(SignedAmount::from_str_in("-2100000000000000", Denomination::Satoshi),
+                   Ok(-SignedAmount::MAX_MONEY));
+    }
+
+    #[test]
+    fn amount_parser_options() {
+        let strict = AmountParser::new().allow_negative(false).allow_plus(false).max_decimals(2);
+        assert_eq!(strict.parse_signed_amount("1.25"), Ok(SignedAmount::from_sat(125_000_000)));
+        assert_eq!(strict.parse_signed_amount("-1"), Err(ParseAmountError::Negative));
+        assert_eq!(strict.parse_signed_amount("-0"), Ok(SignedAmount::zero()));
+        assert_eq!(strict.parse_amount("+1"), Err(ParseAmountError::InvalidCharacter { pos: 0 }));
+        assert_eq!(strict.parse_amount("1.250"), Err(ParseAmountError::TooPrecise));
+
+        let lenient = AmountParser::new().trim_whitespace(true).denomination_suffix(true);
+        assert_eq!(lenient.parse_amount("  1.5 mBTC\n"), Ok(Amount::from_sat(150_000)));
+        assert_eq!(lenient.parse_amount("  1.5. mBTC"), Err(ParseAmountError::InvalidCharacter { pos: 5 }));
+
+        let sats = AmountParser::new()
+            .denomination_suffix(true)
+            .allowed_denominations(&[Denomination::Satoshi, Denomination::MilliSatoshi]);
+        assert_eq!(sats.parse_amount("2100 sat"), Ok(Amount::from_sat(2100)));
+        assert_eq!(sats.parse_amount("1 BTC"),
+                   Err(ParseAmountError::DisallowedDenomination(Denomination::Bitcoin)));
+        assert_eq!(AmountParser::new().allowed_denominations(&[]).parse_amount("1"),
+                   Err(ParseAmountError::DisallowedDenomination(Denomination::Bitcoin)));
+    }
+
+    #[test]
+    fn parse_group_separators() {
+        assert_eq!(Amount::from_str_with_denomination("1_000_000 sat"), Ok(Amount::from_sat(1_000_000)));
+        assert_eq!(Amount::from_str("1_000"), Err(ParseAmountError::InvalidCharacter { pos: 1 }));
+
+        let commas = AmountParser::new().group_separator(',');
+        assert_eq!(commas.parse_amount("1,000,000.000,1"), Ok(Amount::from_sat(100_000_000_010_000)));
+        assert_eq!(commas.parse_amount("1,000"), Ok(Amount::from_sat(100_000_000_000)));
+        assert_eq!(commas.parse_amount(",1"), Err(ParseAmountError::InvalidCharacter { pos: 0 }));
+        assert_eq!(commas.parse_amount("1,"), Err(ParseAmountError::InvalidCharacter { pos: 1 }));
+        assert_eq!(commas.parse_amount("1,,0"), Err(ParseAmountError::InvalidCharacter { pos: 1 }));
+        assert_eq!(commas.parse_amount("1,.0"), Err(ParseAmountError::InvalidCharacter { pos: 1 }));
+        assert_eq!(commas.parse_amount("1.,0"), Err(ParseAmountError::InvalidCharacter { pos: 2 }));
+        assert_eq!(commas.parse_amount("-,1"), Err(ParseAmountError::InvalidCharacter { pos: 1 }));
+
+        let thin_space = AmountParser::new().group_separator('\u{202f}');
+        assert_eq!(thin_space.parse_amount("1\u{202f}000"), Ok(Amount::from_sat(100_000_000_000)));
+        assert_eq!(thin_space.parse_amount("1\u{202f}"), Err(ParseAmountError::InvalidCharacter { pos: 1 }));
+    }
+
+    #[test]
+    fn parse_exponent_exact() {
+        assert_eq!(parse_decimal("1e-5", Denomination::Bitcoin), Ok((false, 1_000)));
+        assert_eq!(parse_decimal("2.53583E-3", Denomination::Bitcoin), Ok((false, 253583)));
+        assert_eq!(parse_decimal("-2.1e+7", Denomination::Bitcoin), Ok((true, 2_100_000_000_000_000)));
+        assert_eq!(parse_decimal("1e8", Denomination::Satoshi), Ok((false, 100_000_000)));
+        assert_eq!(parse_decimal("25000e-3", Denomination::Satoshi), Ok((false, 25)));
+        assert_eq!(parse_decimal("0.0001000e4", Denomination::Bitcoin), Ok((false, 100_000_000)));
+        assert_eq!(parse_decimal("0e999999999999999999999", Denomination::Bitcoin), Ok((false, 0)));
+        assert_eq!(parse_decimal("1e-9", Denomination::Bitcoin), Err(ParseAmountError::TooPrecise));
+        assert_eq!(parse_decimal("1e-999999999999", Denomination::Bitcoin), Err(ParseAmountError::TooPrecise));
+        assert_eq!(parse_decimal("1e12", Denomination::Bitcoin), Err(ParseAmountError::OutOfRange));
+        assert_eq!(parse_decimal("1e99999999", Denomination::Bitcoin), Err(ParseAmountError::OutOfRange));
+        assert_eq!(parse_decimal("1e", Denomination::Bitcoin), Err(ParseAmountError::InvalidCharacter { pos: 1 }));
+        assert_eq!(parse_decimal("1e-", Denomination::Bitcoin), Err(ParseAmountError::InvalidCharacter { pos: 1 }));
+        assert_eq!(parse_decimal("1e5.0", Denomination::Bitcoin), Err(ParseAmountError::InvalidCharacter { pos: 3 }));
+        assert_eq!(parse_decimal("e5", Denomination::Bitcoin), Err(ParseAmountError::InvalidCharacter { pos: 0 }));
+        assert_eq!(parse_decimal("1.000000000000000000000000000000000000000000001", Denomination::Bitcoin),
+                   Err(ParseAmountError::TooPrecise));
+
+        let no_exponent = AmountParser::new().allow_exponent(false);
+        assert_eq!(no_exponent.parse_amount("1e-5"), Err(ParseAmountError::InvalidCharacter { pos: 1 }));
+        assert_eq!(AmountParser::new().max_decimals(2).parse_amount("125e-2"), Ok(Amount::from_sat(125_000_000)));
+        assert_eq!(AmountParser::new().max_decimals(2).parse_amount("1e-3"), Err(ParseAmountError::TooPrecise));
+    }
+
+    #[test]
+    fn parse_sat_str() {
+        assert_eq!(Amount::from_sat_str("253583"), Ok(Amount::from_sat(253583)));
+        assert_eq!(Amount::from_sat_str("2100000000000000"), Ok(Amount::MAX_MONEY));
+        assert_eq!(Amount::from_sat_str("18446744073709551616"), Err(ParseAmountError::OutOfRange));
+        assert_eq!(Amount::from_sat_str("-1"), Err(ParseAmountError::Negative));
+        assert_eq!(Amount::from_sat_str("1.5"), Err(ParseAmountError::TooPrecise));
+        assert_eq!(Amount::from_sat_str("1e3"), Err(ParseAmountError::InvalidCharacter { pos: 1 }));
+        assert_eq!(Amount::from_sat_str("+1"), Err(ParseAmountError::InvalidCharacter { pos: 0 }));
+        assert_eq!(Amount::from_sat_str(""), Err(ParseAmountError::Empty));
+        assert_eq!(SignedAmount::from_sat_str("-253583"), Ok(SignedAmount::from_sat(-253583)));
+        assert_eq!(SignedAmount::from_sat_str("9223372036854775808"), Err(ParseAmountError::OutOfRange));
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn parse_serde_json() {
+        let num = |s: &str| ::serde_json::Number::from_str(s).unwrap();
+        assert_eq!(Amount::from_serde_json(&num("0.29999999")), Ok(Amount::from_sat(29_999_999)));
+        assert_eq!(Amount::from_serde_json(&num("21")), Ok(Amount::from_sat(2_100_000_000)));
+        assert_eq!(Amount::from_serde_json(&num("1e-8")), Ok(Amount::from_sat(1)));
+        assert_eq!(Amount::from_serde_json(&num("-1")), Err(ParseAmountError::Negative));
+        assert_eq!(Amount::from_serde_json(&num("0.000000001")), Err(ParseAmountError::TooPrecise));
+        assert_eq!(SignedAmount::from_serde_json(&num("-0.00253583")), Ok(SignedAmount::from_sat(-253_583)));
+        assert_eq!(SignedAmount::from_serde_json(&num("1e30")), Err(ParseAmountError::OutOfRange));
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn parse_serde_json_sat() {
+        use serde_json::Value;
+
+        let json = |s: &str| Value::from_str(s).unwrap();
+        assert_eq!(Amount::try_from(json("253583")), Ok(Amount::from_sat(253583)));
+        assert_eq!(Amount::try_from(&json("\"253583\"")), Ok(Amount::from_sat(253583)));
+        assert_eq!(Amount::try_from(json("-1")), Err(ParseAmountError::Negative));
+        assert_eq!(Amount::try_from(json("0.5")), Err(ParseAmountError::TooPrecise));
+        assert_eq!(Amount::try_from(json("null")), Err(ParseAmountError::NotANumber));
+        assert_eq!(SignedAmount::try_from(json("\"-141\"")), Ok(SignedAmount::from_sat(-141)));
+        assert_eq!(SignedAmount::try_from(json("9223372036854775808")), Err(ParseAmountError::OutOfRange));
+    }
+
+    #[cfg(feature = "strason")]
+    #[test]
+    fn parse_strason_json() {
+        let json = |s: &str| ::strason::Json::from_str(s).unwrap();
+        assert_eq!(Amount::from_strason_json(&json("0.29999999")), Ok(Amount::from_sat(29_999_999)));
+        assert_eq!(Amount::from_strason_json(&json("-1")), Err(ParseAmountError::Negative));
+        assert_eq!(Amount::from_strason_json(&json("\"1\"")), Err(ParseAmountError::NotANumber));
+        assert_eq!(Amount::from_strason_json(&json("null")), Err(ParseAmountError::NotANumber));
+        assert_eq!(SignedAmount::from_strason_json(&json("-20999999.99999999")),
+                   Ok(SignedAmount::from_sat(-2_099_999_999_999_999)));
+        assert_eq!(SignedAmount::from_strason_json(&json("0.000000001")),
+                   Err(ParseAmountError::TooPrecise));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn parse_batch() {
+        let input = b"0.5,1.25,-1,abc,\xff,0.00000001";
+        let mut ranges = Vec::new();
+        let mut start = 0;
+        for (i, &c) in input.iter().enumerate().chain(Some((input.len(), &b','))) {
+            if c == b',' {
+                ranges.push(start..i);
+                start = i + 1;
+            }
+        }
+
+        let (amounts, errors) = AmountParser::new().parse_amounts(input, &ranges);
+        assert_eq!(amounts, [Amount::from_sat(50_000_000), Amount::from_sat(125_000_000), Amount::zero(),
+                             Amount::zero(), Amount::zero(), Amount::from_sat(1)]);
+        assert_eq!(errors, [(2, ParseAmountError::Negative),
+                            (3, ParseAmountError::InvalidCharacter { pos: 0 }),
+                            (4, ParseAmountError::InvalidCharacter { pos: 0 })]);
+
+        let (amounts, errors) = AmountParser::new().parse_signed_amounts(input, &ranges[..3]);
+        assert_eq!(amounts, [SignedAmount::from_sat(50_000_000), SignedAmount::from_sat(125_000_000),
+                             SignedAmount::from_sat(-100_000_000)]);
+        assert!(errors.is_empty());
+    }
+}
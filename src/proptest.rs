@@ -0,0 +1,84 @@
+//! `proptest` strategies generating amounts.
+//!
+//! The strategies shrink towards the low end of their range, zero for the
+//! ones covering it.
+//!
+//! `any_amount` and `any_signed_amount` generate invalid amounts even with
+//! the `strict-money` feature, whose assertion then fails in the code under
+//! test rather than in the strategy, use `valid_money` and
+//! `valid_signed_money` for tests that only want valid ones.
+
+use std::ops::Range;
+
+use proptest_crate::strategy::Strategy;
+
+use {Amount, SignedAmount};
+
+/// Generates any `Amount`, including the ones out of the valid money range.
+pub fn any_amount() -> impl Strategy<Value = Amount> {
+    (0..=u64::MAX).prop_map(Amount::from_sat_unasserted)
+}
+
+/// Generates any `SignedAmount`, including the ones out of the valid money
+/// range.
+pub fn any_signed_amount() -> impl Strategy<Value = SignedAmount> {
+    (i64::MIN..=i64::MAX).prop_map(SignedAmount::from_sat_unasserted)
+}
+
+/// Generates amounts within `range`, e.g. `amount_in_range(a..b)`.
+///
+/// # Panics
+///
+/// Panics if `range` is empty.
+pub fn amount_in_range(range: Range<Amount>) -> impl Strategy<Value = Amount> {
+    (range.start.into_inner()..range.end.into_inner()).prop_map(Amount::from_sat_unasserted)
+}
+
+/// Generates signed amounts within `range`.
+///
+/// # Panics
+///
+/// Panics if `range` is empty.
+pub fn signed_amount_in_range(range: Range<SignedAmount>) -> impl Strategy<Value = SignedAmount> {
+    (range.start.into_inner()..range.end.into_inner()).prop_map(SignedAmount::from_sat_unasserted)
+}
+
+/// Generates amounts within the valid money range, `0..=MAX_MONEY`.
+pub fn valid_money() -> impl Strategy<Value = Amount> {
+    (0..=Amount::MAX_MONEY.into_inner()).prop_map(Amount::from_sat)
+}
+
+/// Generates signed amounts within the valid money range,
+/// `-MAX_MONEY..=MAX_MONEY`.
+pub fn valid_signed_money() -> impl Strategy<Value = SignedAmount> {
+    let max = SignedAmount::MAX_MONEY.into_inner();
+    (-max..=max).prop_map(SignedAmount::from_sat)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest_crate::test_runner::TestRunner;
+
+    use super::*;
+
+    #[test]
+    fn proptest_strategies() {
+        let mut runner = TestRunner::deterministic();
+        runner.run(&valid_money(), |amt| {
+            assert!(amt.is_valid_money());
+            assert_eq!(amt.to_string().parse::<Amount>().unwrap(), amt);
+            Ok(())
+        }).unwrap();
+
+        runner.run(&valid_signed_money(), |amt| {
+            assert!(amt.is_valid_money());
+            Ok(())
+        }).unwrap();
+
+        let (low, high) = (Amount::from_sat(546), Amount::from_sat(100_000));
+        runner.run(&amount_in_range(low..high), |amt| {
+            assert!(low <= amt && amt < high);
+            Ok(())
+        }).unwrap();
+    }
+}
@@ -0,0 +1,134 @@
+//! Protobuf messages for amounts, matching `proto/bitcoin_amount.proto`.
+//!
+//! The messages convert from and to `Amount` and `SignedAmount`, checking
+//! the sign and range, and decimal strings are parsed exactly.
+
+use std::convert::TryFrom;
+
+use {OutOfRangeError, ParseAmountError, SignedAmount};
+
+/// An amount as an integer number of satoshis, negative for debits.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct Amount {
+    /// The number of satoshis.
+    #[prost(int64, tag = "1")]
+    pub sat: i64,
+}
+
+/// An amount as a decimal BTC string with up to 8 decimal places, e.g.
+/// `"0.00253583"`.
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct DecimalAmount {
+    /// The decimal number of BTC.
+    #[prost(string, tag = "1")]
+    pub btc: String,
+}
+
+impl From<SignedAmount> for Amount {
+    fn from(amt: SignedAmount) -> Amount {
+        Amount { sat: amt.into_inner() }
+    }
+}
+
+impl TryFrom<::Amount> for Amount {
+    type Error = OutOfRangeError;
+
+    fn try_from(amt: ::Amount) -> Result<Amount, OutOfRangeError> {
+        amt.to_signed().map(Amount::from)
+    }
+}
+
+impl TryFrom<Amount> for SignedAmount {
+    type Error = OutOfRangeError;
+
+    fn try_from(msg: Amount) -> Result<SignedAmount, OutOfRangeError> {
+        SignedAmount::try_from_sat(msg.sat)
+    }
+}
+
+impl TryFrom<Amount> for ::Amount {
+    type Error = OutOfRangeError;
+
+    fn try_from(msg: Amount) -> Result<::Amount, OutOfRangeError> {
+        ::Amount::try_from(msg.sat)
+    }
+}
+
+impl From<::Amount> for DecimalAmount {
+    fn from(amt: ::Amount) -> DecimalAmount {
+        DecimalAmount { btc: amt.to_string() }
+    }
+}
+
+impl From<SignedAmount> for DecimalAmount {
+    fn from(amt: SignedAmount) -> DecimalAmount {
+        DecimalAmount { btc: amt.to_string() }
+    }
+}
+
+impl<'a> TryFrom<&'a DecimalAmount> for ::Amount {
+    type Error = ParseAmountError;
+
+    fn try_from(msg: &'a DecimalAmount) -> Result<::Amount, ParseAmountError> {
+        msg.btc.parse()
+    }
+}
+
+impl TryFrom<DecimalAmount> for ::Amount {
+    type Error = ParseAmountError;
+
+    fn try_from(msg: DecimalAmount) -> Result<::Amount, ParseAmountError> {
+        ::Amount::try_from(&msg)
+    }
+}
+
+impl<'a> TryFrom<&'a DecimalAmount> for SignedAmount {
+    type Error = ParseAmountError;
+
+    fn try_from(msg: &'a DecimalAmount) -> Result<SignedAmount, ParseAmountError> {
+        msg.btc.parse()
+    }
+}
+
+impl TryFrom<DecimalAmount> for SignedAmount {
+    type Error = ParseAmountError;
+
+    fn try_from(msg: DecimalAmount) -> Result<SignedAmount, ParseAmountError> {
+        SignedAmount::try_from(&msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use prost::Message;
+
+    use super::*;
+
+    #[test]
+    fn proto_amount_conversions() {
+        let msg = Amount::try_from(::Amount::from_sat(253583)).unwrap();
+        assert_eq!(msg, Amount { sat: 253583 });
+        assert_eq!(::Amount::try_from(msg), Ok(::Amount::from_sat(253583)));
+        assert_eq!(SignedAmount::try_from(Amount { sat: -1 }), Ok(SignedAmount::from_sat(-1)));
+        assert!(::Amount::try_from(Amount { sat: -1 }).is_err());
+        assert!(Amount::try_from(::Amount::max_value()).is_err());
+        #[cfg(feature = "strict-money")]
+        assert!(SignedAmount::try_from(Amount { sat: i64::MAX }).is_err());
+
+        let bytes = msg.encode_to_vec();
+        assert_eq!(Amount::decode(&bytes[..]), Ok(msg));
+    }
+
+    #[test]
+    fn proto_decimal_amount_conversions() {
+        let msg = DecimalAmount::from(SignedAmount::from_sat(-253583));
+        assert_eq!(msg.btc, "-0.00253583");
+        assert_eq!(SignedAmount::try_from(&msg), Ok(SignedAmount::from_sat(-253583)));
+        assert_eq!(::Amount::try_from(msg), Err(ParseAmountError::Negative));
+
+        let msg = DecimalAmount { btc: "0.000000001".to_owned() };
+        assert_eq!(::Amount::try_from(msg), Err(ParseAmountError::TooPrecise));
+    }
+}
@@ -0,0 +1,79 @@
+//! `quickcheck::Arbitrary` implementations of amounts.
+//!
+//! Arbitrary amounts are within the valid money range, and often round to a
+//! whole number of BTC, mBTC or bits, like the amounts people actually use.
+//! They shrink towards zero, trying the round values below them first.
+
+use std::iter;
+
+use quickcheck_crate::{Arbitrary, Gen};
+
+use {Amount, SignedAmount};
+
+/// The units amounts are rounded to, one satoshi being more likely.
+static UNITS: &[u64] = &[1, 1, 1, 100, 100_000, 100_000_000];
+
+macro_rules! impl_quickcheck {
+    ($ty:ident, $inner:ty) => {
+        impl Arbitrary for $ty {
+            fn arbitrary(g: &mut Gen) -> $ty {
+                let unit = *g.choose(UNITS).unwrap() as $inner;
+                let sat = <$inner>::arbitrary(g) % ($ty::MAX_MONEY.into_inner() + 1);
+                $ty::from_sat(sat / unit * unit)
+            }
+
+            fn shrink(&self) -> Box<dyn Iterator<Item = $ty>> {
+                let sat = self.into_inner();
+                if sat == 0 {
+                    return Box::new(iter::empty());
+                }
+
+                let mut round = UNITS.iter()
+                    .rev()
+                    .map(|&unit| sat / unit as $inner * unit as $inner)
+                    .filter(|&r| r != 0 && r != sat)
+                    .collect::<Vec<_>>();
+                round.dedup();
+
+                Box::new(iter::once(0)
+                             .chain(round)
+                             .chain(sat.shrink().filter(|&s| s != 0))
+                             .map($ty::from_sat))
+            }
+        }
+    };
+}
+
+impl_quickcheck!(Amount, u64);
+impl_quickcheck!(SignedAmount, i64);
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_crate::{Arbitrary, Gen};
+
+    use {Amount, SignedAmount};
+
+    #[test]
+    fn quickcheck_arbitrary() {
+        let mut g = Gen::new(100);
+        for _ in 0..1000 {
+            assert!(Amount::arbitrary(&mut g).is_valid_money());
+            assert!(SignedAmount::arbitrary(&mut g).is_valid_money());
+        }
+    }
+
+    #[test]
+    fn quickcheck_shrink() {
+        assert_eq!(Amount::zero().shrink().count(), 0);
+
+        let shrunk = Amount::from_sat(123_456_789).shrink().take(4).collect::<Vec<_>>();
+        assert_eq!(shrunk, vec![Amount::zero(),
+                                Amount::from_sat(100_000_000),
+                                Amount::from_sat(123_400_000),
+                                Amount::from_sat(123_456_700)]);
+        assert!(Amount::from_sat(123_456_789).shrink().all(|a| a < Amount::from_sat(123_456_789)));
+
+        let shrunk = SignedAmount::from_sat(-150_000).shrink().take(2).collect::<Vec<_>>();
+        assert_eq!(shrunk, vec![SignedAmount::zero(), SignedAmount::from_sat(-100_000)]);
+    }
+}
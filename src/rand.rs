@@ -0,0 +1,103 @@
+//! Random amounts with `rand`.
+//!
+//! Amounts implement `SampleUniform`, so they can be sampled from ranges with
+//! `Rng::random_range`, and the `StandardUniform` distribution samples any
+//! amount, even with the `strict-money` feature, whose assertion then fails
+//! where the amount is used rather than when it's sampled. [`ValidMoney`]
+//! samples only amounts within the consensus valid range, which is what
+//! most tests and simulations want.
+
+use rand_crate::distr::uniform::{Error, SampleBorrow, SampleUniform, UniformInt, UniformSampler};
+use rand_crate::distr::{Distribution, StandardUniform};
+use rand_crate::Rng;
+
+use {Amount, SignedAmount};
+
+/// Samples amounts uniformly from the range of valid amounts of money,
+/// `-MAX_MONEY..=MAX_MONEY` for signed amounts and `0..=MAX_MONEY` for
+/// unsigned ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ValidMoney;
+
+/// The uniform sampler of `Amount` ranges.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UniformAmount(UniformInt<u64>);
+
+/// The uniform sampler of `SignedAmount` ranges.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UniformSignedAmount(UniformInt<i64>);
+
+macro_rules! impl_rand {
+    ($ty:ident, $inner:ty, $sampler:ident, $min_money:expr) => {
+        impl UniformSampler for $sampler {
+            type X = $ty;
+
+            fn new<B1, B2>(low: B1, high: B2) -> Result<$sampler, Error>
+                where B1: SampleBorrow<$ty> + Sized,
+                      B2: SampleBorrow<$ty> + Sized
+            {
+                UniformInt::<$inner>::new(low.borrow().into_inner(), high.borrow().into_inner())
+                    .map($sampler)
+            }
+
+            fn new_inclusive<B1, B2>(low: B1, high: B2) -> Result<$sampler, Error>
+                where B1: SampleBorrow<$ty> + Sized,
+                      B2: SampleBorrow<$ty> + Sized
+            {
+                UniformInt::<$inner>::new_inclusive(low.borrow().into_inner(),
+                                                    high.borrow().into_inner())
+                    .map($sampler)
+            }
+
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> $ty {
+                $ty::from_sat_unasserted(self.0.sample(rng))
+            }
+        }
+
+        impl SampleUniform for $ty {
+            type Sampler = $sampler;
+        }
+
+        impl Distribution<$ty> for StandardUniform {
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> $ty {
+                $ty::from_sat_unasserted(rng.random())
+            }
+        }
+
+        impl Distribution<$ty> for ValidMoney {
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> $ty {
+                rng.random_range($min_money..=$ty::MAX_MONEY)
+            }
+        }
+    };
+}
+
+impl_rand!(Amount, u64, UniformAmount, Amount::zero());
+impl_rand!(SignedAmount, i64, UniformSignedAmount, -SignedAmount::MAX_MONEY);
+
+#[cfg(test)]
+mod tests {
+    use rand_crate::rngs::SmallRng;
+    use rand_crate::{Rng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn rand_ranges() {
+        let mut rng = SmallRng::seed_from_u64(21);
+        for _ in 0..1000 {
+            let amt = rng.random_range(Amount::zero()..Amount::from_btc(1.0));
+            assert!(amt < Amount::from_sat(100_000_000));
+
+            let amt = rng.random_range(SignedAmount::from_sat(-5)..=SignedAmount::from_sat(5));
+            assert!(amt.into_inner().abs() <= 5);
+
+            let amt: Amount = rng.sample(ValidMoney);
+            assert!(amt.is_valid_money());
+            let amt: SignedAmount = rng.sample(ValidMoney);
+            assert!(amt.is_valid_money());
+        }
+
+        assert!(UniformAmount::new(Amount::from_sat(1), Amount::from_sat(1)).is_err());
+    }
+}
@@ -0,0 +1,80 @@
+//! Conversions between amounts and Redis values.
+//!
+//! Amounts are written as integers of satoshis, so `INCRBY` and `DECRBY`
+//! work on them. Reading accepts Redis integers and strings of integer
+//! satoshis.
+
+use std::convert::TryFrom;
+use std::str;
+
+use redis_crate::{FromRedisValue, NumericBehavior, ParsingError, RedisWrite, ToRedisArgs,
+                  ToSingleRedisArg, Value};
+
+use {Amount, SignedAmount};
+
+macro_rules! impl_redis {
+    ($ty:ident) => {
+        /// Writes the amount as an integer of satoshis.
+        impl ToRedisArgs for $ty {
+            fn write_redis_args<W: ?Sized + RedisWrite>(&self, out: &mut W) {
+                out.write_arg(self.into_inner().to_string().as_bytes())
+            }
+
+            fn describe_numeric_behavior(&self) -> NumericBehavior {
+                NumericBehavior::NumberIsInteger
+            }
+        }
+
+        impl ToSingleRedisArg for $ty {}
+
+        /// Reads an integer of satoshis, or a string of one.
+        impl FromRedisValue for $ty {
+            fn from_redis_value_ref(v: &Value) -> Result<$ty, ParsingError> {
+                match *v {
+                    Value::Int(sat) => {
+                        $ty::try_from(sat).map_err(|e| ParsingError::from(e.to_string()))
+                    }
+                    Value::BulkString(ref bytes) => {
+                        let s = str::from_utf8(bytes)?;
+                        $ty::from_sat_str(s).map_err(|e| ParsingError::from(e.to_string()))
+                    }
+                    Value::SimpleString(ref s) => {
+                        $ty::from_sat_str(s).map_err(|e| ParsingError::from(e.to_string()))
+                    }
+                    Value::Attribute { ref data, .. } => $ty::from_redis_value_ref(data),
+                    _ => Err(ParsingError::from(format!("not an amount (value was {:?})", v))),
+                }
+            }
+
+            fn from_redis_value(v: Value) -> Result<$ty, ParsingError> {
+                $ty::from_redis_value_ref(&v)
+            }
+        }
+    };
+}
+
+impl_redis!(Amount);
+impl_redis!(SignedAmount);
+
+#[cfg(test)]
+mod tests {
+    use redis_crate::{FromRedisValue, ToRedisArgs, Value};
+
+    use {Amount, SignedAmount};
+
+    #[test]
+    fn redis_integer() {
+        assert_eq!(Amount::from_sat(253583).to_redis_args(), vec![b"253583".to_vec()]);
+        assert_eq!(SignedAmount::from_sat(-1).to_redis_args(), vec![b"-1".to_vec()]);
+
+        let read = |v: Value| Amount::from_redis_value(v).map_err(|e| e.to_string());
+        assert_eq!(read(Value::Int(253583)), Ok(Amount::from_sat(253583)));
+        assert_eq!(read(Value::BulkString(b"253583".to_vec())), Ok(Amount::from_sat(253583)));
+        assert_eq!(read(Value::SimpleString("546".to_owned())), Ok(Amount::from_sat(546)));
+        assert_eq!(SignedAmount::from_redis_value(Value::Int(-1)).unwrap(), SignedAmount::from_sat(-1));
+
+        assert!(read(Value::Int(-1)).is_err());
+        assert!(read(Value::BulkString(b"0.5".to_vec())).is_err());
+        assert!(read(Value::Nil).is_err());
+    }
+}
@@ -0,0 +1,88 @@
+//! Conversions between amounts and SQLite values.
+//!
+//! Amounts are stored as INTEGER satoshis. Reading checks the value is an
+//! INTEGER that fits in the amount type, e.g. negative values aren't valid
+//! `Amount`s.
+
+use std::convert::TryFrom;
+
+use rusqlite_crate::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use rusqlite_crate::{Error, Result};
+
+use {Amount, SignedAmount};
+
+/// Converts the amount to an INTEGER number of satoshis, failing with
+/// `Error::ToSqlConversionFailure` above `i64::MAX` satoshis.
+impl ToSql for Amount {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        i64::try_from(*self)
+            .map(ToSqlOutput::from)
+            .map_err(|e| Error::ToSqlConversionFailure(Box::new(e)))
+    }
+}
+
+/// Converts the amount to an INTEGER number of satoshis.
+impl ToSql for SignedAmount {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.into_inner()))
+    }
+}
+
+/// Converts an INTEGER number of satoshis, failing with
+/// `FromSqlError::OutOfRange` if it's negative.
+impl FromSql for Amount {
+    fn column_result(value: ValueRef) -> FromSqlResult<Amount> {
+        let sat = value.as_i64()?;
+        Amount::try_from(sat).map_err(|_| FromSqlError::OutOfRange(sat))
+    }
+}
+
+/// Converts an INTEGER number of satoshis, failing with
+/// `FromSqlError::OutOfRange` only with the `strict-money` feature enabled,
+/// if it isn't valid money.
+impl FromSql for SignedAmount {
+    fn column_result(value: ValueRef) -> FromSqlResult<SignedAmount> {
+        let sat = value.as_i64()?;
+        SignedAmount::try_from_sat(sat).map_err(|_| FromSqlError::OutOfRange(sat))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite_crate::{Connection, Error};
+
+    use {Amount, SignedAmount};
+
+    #[test]
+    fn rusqlite_integer() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE utxos (value INTEGER NOT NULL)", ()).unwrap();
+        conn.execute("INSERT INTO utxos VALUES (?1), (?2)",
+                     (Amount::from_sat(253583), SignedAmount::from_sat(-1))).unwrap();
+
+        let mut stmt = conn.prepare("SELECT value FROM utxos ORDER BY value DESC").unwrap();
+        let mut rows = stmt.query(()).unwrap();
+        let row = rows.next().unwrap().unwrap();
+        assert_eq!(row.get::<_, Amount>(0).unwrap(), Amount::from_sat(253583));
+        let row = rows.next().unwrap().unwrap();
+        assert_eq!(row.get::<_, SignedAmount>(0).unwrap(), SignedAmount::from_sat(-1));
+        match row.get::<_, Amount>(0) {
+            Err(Error::IntegralValueOutOfRange(0, -1)) => {}
+            res => panic!("unexpected result {:?}", res),
+        }
+
+        match conn.query_row("SELECT 0.5", (), |row| row.get::<_, Amount>(0)) {
+            Err(Error::InvalidColumnType(..)) => {}
+            res => panic!("unexpected result {:?}", res),
+        }
+        match conn.execute("INSERT INTO utxos VALUES (?1)", [Amount::max_value()]) {
+            Err(Error::ToSqlConversionFailure(_)) => {}
+            res => panic!("unexpected result {:?}", res),
+        }
+        #[cfg(feature = "strict-money")]
+        match conn.query_row("SELECT 9223372036854775807", (), |row| row.get::<_, SignedAmount>(0)) {
+            Err(Error::IntegralValueOutOfRange(0, i64::MAX)) => {}
+            res => panic!("unexpected result {:?}", res),
+        }
+    }
+}
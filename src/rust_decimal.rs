@@ -0,0 +1,89 @@
+//! Conversions between amounts and `rust_decimal` numbers of BTC.
+//!
+//! Converting an amount to a `Decimal` is exact, with a scale of 8.
+//! Converting back is checked: sub-satoshi precision is rejected instead of
+//! rounded, as are values that don't fit in the amount type.
+
+use std::convert::TryFrom;
+
+use rust_decimal_crate::Decimal;
+
+use {Amount, ParseAmountError, SignedAmount};
+
+/// The scale of BTC `Decimal`s, one satoshi.
+const SCALE: u32 = 8;
+
+/// Converts a `Decimal` number of BTC to satoshis exactly.
+fn decimal_to_sat(btc: Decimal) -> Result<i128, ParseAmountError> {
+    let (mantissa, scale) = (btc.mantissa(), btc.scale());
+    if scale <= SCALE {
+        // Decimals have at most 96 bits of mantissa, so this can't overflow.
+        Ok(mantissa * 10i128.pow(SCALE - scale))
+    } else {
+        let divisor = 10i128.pow(scale - SCALE);
+        if mantissa % divisor != 0 {
+            return Err(ParseAmountError::TooPrecise);
+        }
+        Ok(mantissa / divisor)
+    }
+}
+
+macro_rules! impl_rust_decimal {
+    ($ty:ident) => {
+        /// Converts the amount to a number of BTC with 8 decimal places, e.g.
+        /// `0.00253583`.
+        impl From<$ty> for Decimal {
+            fn from(amt: $ty) -> Decimal {
+                Decimal::from_i128_with_scale(i128::from(amt), SCALE)
+            }
+        }
+
+        /// Converts a number of BTC exactly, sub-satoshi precision is
+        /// rejected.
+        impl TryFrom<Decimal> for $ty {
+            type Error = ParseAmountError;
+
+            fn try_from(btc: Decimal) -> Result<$ty, ParseAmountError> {
+                let sat = decimal_to_sat(btc)?;
+                if sat < 0 && $ty::min_value().is_zero() {
+                    return Err(ParseAmountError::Negative);
+                }
+                $ty::try_from(sat).map_err(|_| ParseAmountError::OutOfRange)
+            }
+        }
+    };
+}
+
+impl_rust_decimal!(Amount);
+impl_rust_decimal!(SignedAmount);
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+    use std::str::FromStr;
+
+    use rust_decimal_crate::Decimal;
+
+    use {Amount, ParseAmountError, SignedAmount};
+
+    #[test]
+    fn rust_decimal_conversions() {
+        let dec = |s: &str| Decimal::from_str(s).unwrap();
+        assert_eq!(Decimal::from(Amount::from_sat(253583)).to_string(), "0.00253583");
+        assert_eq!(Decimal::from(SignedAmount::from_sat(-100_000_000)).to_string(), "-1.00000000");
+
+        assert_eq!(Amount::try_from(dec("0.00253583")), Ok(Amount::from_sat(253583)));
+        assert_eq!(Amount::try_from(dec("21")), Ok(Amount::from_sat(2_100_000_000)));
+        assert_eq!(Amount::try_from(dec("0.0000000100")), Ok(Amount::from_sat(1)));
+        assert_eq!(SignedAmount::try_from(dec("-0.5")), Ok(SignedAmount::from_sat(-50_000_000)));
+
+        assert_eq!(Amount::try_from(dec("0.000000001")), Err(ParseAmountError::TooPrecise));
+        assert_eq!(Amount::try_from(dec("-1")), Err(ParseAmountError::Negative));
+        assert_eq!(SignedAmount::try_from(dec("100000000000")), Err(ParseAmountError::OutOfRange));
+        let max = Amount::try_from(Decimal::from(Amount::max_value()));
+        #[cfg(feature = "strict-money")]
+        assert_eq!(max, Err(ParseAmountError::OutOfRange));
+        #[cfg(not(feature = "strict-money"))]
+        assert_eq!(max, Ok(Amount::max_value()));
+    }
+}
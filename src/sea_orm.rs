@@ -0,0 +1,105 @@
+//! SeaORM conversions of amounts to and from `BigInt` columns of satoshis.
+//!
+//! Entities can declare `Amount` and `SignedAmount` columns, including
+//! nullable `Option<Amount>` ones. Negative values can't be read as
+//! `Amount`s.
+
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use sea_orm_crate::sea_query::{ArrayType, ColumnType, Nullable, Value, ValueType, ValueTypeErr};
+use sea_orm_crate::{ColIdx, DbErr, QueryResult, TryGetError, TryGetable};
+
+use {Amount, SignedAmount};
+
+/// Converts the amount to a `BigInt` number of satoshis.
+///
+/// # Panics
+///
+/// Panics if the amount is above `i64::MAX` satoshis.
+impl From<Amount> for Value {
+    fn from(amt: Amount) -> Value {
+        Value::BigInt(Some(<i64 as TryFrom<_>>::try_from(amt).expect("amount out of range of BIGINT")))
+    }
+}
+
+/// Converts the amount to a `BigInt` number of satoshis.
+impl From<SignedAmount> for Value {
+    fn from(amt: SignedAmount) -> Value {
+        Value::BigInt(Some(amt.into_inner()))
+    }
+}
+
+macro_rules! impl_sea_orm {
+    ($ty:ident) => {
+        impl ValueType for $ty {
+            fn try_from(v: Value) -> Result<$ty, ValueTypeErr> {
+                match v {
+                    Value::BigInt(Some(sat)) => <$ty as TryFrom<_>>::try_from(sat).map_err(|_| ValueTypeErr),
+                    _ => Err(ValueTypeErr),
+                }
+            }
+
+            fn type_name() -> String {
+                stringify!($ty).to_owned()
+            }
+
+            fn array_type() -> ArrayType {
+                ArrayType::BigInt
+            }
+
+            fn column_type() -> ColumnType {
+                ColumnType::BigInteger
+            }
+        }
+
+        impl Nullable for $ty {
+            fn null() -> Value {
+                Value::BigInt(None)
+            }
+        }
+
+        /// Reads a `BigInt` number of satoshis.
+        impl TryGetable for $ty {
+            fn try_get_by<I: ColIdx>(res: &QueryResult, index: I) -> Result<$ty, TryGetError> {
+                let sat = i64::try_get_by(res, index)?;
+                <$ty as TryFrom<_>>::try_from(sat).map_err(|e| {
+                    TryGetError::DbErr(DbErr::TryIntoErr {
+                        from: "i64",
+                        into: stringify!($ty),
+                        source: Arc::new(e),
+                    })
+                })
+            }
+        }
+    };
+}
+
+impl_sea_orm!(Amount);
+impl_sea_orm!(SignedAmount);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use sea_orm_crate::sea_query::{Value, ValueType};
+    use sea_orm_crate::{ProxyRow, QueryResult, TryGetable};
+
+    use {Amount, SignedAmount};
+
+    #[test]
+    fn sea_orm_bigint() {
+        assert_eq!(Value::from(Amount::from_sat(253583)), Value::BigInt(Some(253583)));
+        assert_eq!(<Amount as ValueType>::try_from(Value::BigInt(Some(546))).unwrap(), Amount::from_sat(546));
+        assert!(<Amount as ValueType>::try_from(Value::BigInt(Some(-1))).is_err());
+        assert_eq!(Value::from(None::<SignedAmount>), Value::BigInt(None));
+
+        let mut values = BTreeMap::new();
+        values.insert("value".to_owned(), Value::BigInt(Some(-1)));
+        values.insert("fee".to_owned(), Value::BigInt(None));
+        let res = QueryResult::from(ProxyRow::new(values));
+        assert_eq!(SignedAmount::try_get_by(&res, "value").unwrap(), SignedAmount::from_sat(-1));
+        assert!(Amount::try_get_by(&res, "value").is_err());
+        assert_eq!(Option::<Amount>::try_get_by(&res, "fee").unwrap(), None);
+    }
+}
@@ -0,0 +1,1589 @@
+//! Serde helpers to (de)serialize amounts in different representations.
+//!
+//! The `Serialize` and `Deserialize` implementations of the amount types
+//! check `is_human_readable()`: formats such as JSON or TOML use a decimal
+//! BTC string, while binary formats such as bincode or CBOR use the inner
+//! satoshis. The modules here are meant for `#[serde(with = "...")]` fields
+//! that need a fixed representation regardless of the format:
+//!
+//! - `as_sat`: an integer number of satoshis.
+//! - `as_btc`: a floating point number of BTC, which loses precision for
+//!   amounts above 2^53 satoshis. With the `serde_json` feature, JSON numbers
+//!   are parsed exactly from their decimal text instead of through an `f64`,
+//!   and sub-satoshi precision is rejected.
+//! - `as_str`: a decimal BTC string with 8 decimal places, e.g.
+//!   `"0.00253583"`.
+//! - `as_btc_json`: a JSON number of BTC with exactly 8 decimal places, e.g.
+//!   `0.00253583`, only for `serde_json` and with the `serde_json` feature.
+//! - `as_sat_str`: a string of satoshis, e.g. `"253583"`.
+//! - `as_sat_lenient`: an integer number of satoshis, also deserialized from
+//!   a string of them, for Esplora, Electrum and other block explorer APIs.
+//! - `lenient`: deserializes an integer number of satoshis, a floating point
+//!   number of BTC or a string holding either, for APIs that mix them.
+//! - `as_str_in`: a decimal string in a chosen denomination, e.g.
+//!   `as_str_in::mbtc` for `"2.53583"`, for CSV and other flat formats.
+//! - `with_denomination`: a string with the denomination, e.g.
+//!   `"0.25 BTC"` or `"150000 sat"`, for TOML, YAML and other configuration
+//!   files.
+//! - `as_tagged_sat` and `as_tagged_btc`: an object making the unit
+//!   explicit, `{"sat": 253583}` or `{"btc": "0.00253583"}`, either of them
+//!   is accepted when deserializing.
+//!
+//! Each of them has an `opt` submodule, e.g. `as_btc::opt`, for
+//! `Option<Amount>` fields where `None` is `null`.
+//!
+//! With the `utoipa` feature the amount types implement `ToSchema` for their
+//! default representation in JSON. With the `schemars` feature they
+//! implement `JsonSchema` likewise, and every module has a `json_schema`
+//! function for `#[schemars(schema_with = "...")]`, e.g.
+//! `#[schemars(schema_with = "bitcoin_amount::serde::as_sat::json_schema")]`.
+
+#[cfg(any(feature = "schemars", feature = "utoipa"))]
+use std::borrow::Cow;
+use std::fmt::{self, Formatter};
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+#[cfg(feature = "schemars")]
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+#[cfg(feature = "utoipa")]
+use utoipa::openapi::{self, ObjectBuilder, RefOr, Type};
+use serde_crate::de::{self, Deserializer, MapAccess, Unexpected, Visitor};
+use serde_crate::ser::SerializeMap;
+use serde_crate::{Deserialize, Serialize, Serializer};
+
+use {Amount, Denomination, FromBtcError, ParseAmountError, SignedAmount, SAT_PER_BTC};
+
+/// An amount type that can be (de)serialized by the modules of `serde`.
+///
+/// This trait is sealed, it's only implemented by `Amount` and
+/// `SignedAmount`.
+pub trait SerdeAmount: Copy + Sized + private::Sealed {
+    /// Serializes the amount as an integer number of satoshis.
+    fn ser_sat<S: Serializer>(self, s: S) -> Result<S::Ok, S::Error>;
+    /// Deserializes the amount from an integer number of satoshis.
+    fn des_sat<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error>;
+    /// Serializes the amount as a string of satoshis.
+    fn ser_sat_str<S: Serializer>(self, s: S) -> Result<S::Ok, S::Error>;
+    /// Deserializes the amount from a string of satoshis.
+    fn des_sat_str<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error>;
+    /// Deserializes the amount from an integer number of satoshis or a
+    /// string of them.
+    fn des_sat_lenient<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error>;
+    /// Serializes the amount as a floating point number of BTC.
+    fn ser_btc<S: Serializer>(self, s: S) -> Result<S::Ok, S::Error>;
+    /// Deserializes the amount from a floating point number of BTC.
+    fn des_btc<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error>;
+    /// Serializes the amount as an exact `serde_json` number of BTC.
+    #[cfg(feature = "serde_json")]
+    fn ser_btc_json<S: Serializer>(self, s: S) -> Result<S::Ok, S::Error>;
+    /// Serializes the amount as a decimal BTC string.
+    fn ser_str<S: Serializer>(self, s: S) -> Result<S::Ok, S::Error>;
+    /// Deserializes the amount from a decimal BTC string.
+    fn des_str<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error>;
+    /// Deserializes the amount from an integer number of satoshis, a
+    /// floating point number of BTC or a string holding either.
+    fn des_lenient<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error>;
+    /// Serializes the amount as a `{"sat": 253583}` object.
+    fn ser_tagged_sat<S: Serializer>(self, s: S) -> Result<S::Ok, S::Error>;
+    /// Serializes the amount as a `{"btc": "0.00253583"}` object.
+    fn ser_tagged_btc<S: Serializer>(self, s: S) -> Result<S::Ok, S::Error>;
+    /// Deserializes the amount from either a `{"sat": 253583}` or a
+    /// `{"btc": "0.00253583"}` object.
+    fn des_tagged<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error>;
+    /// Serializes the amount as a decimal string in `denom`, without the
+    /// denomination suffix.
+    fn ser_str_in<S: Serializer>(self, denom: Denomination, s: S) -> Result<S::Ok, S::Error>;
+    /// Deserializes the amount from a decimal string in `denom`.
+    fn des_str_in<'de, D: Deserializer<'de>>(denom: Denomination, d: D) -> Result<Self, D::Error>;
+    /// Serializes the amount as a decimal BTC string followed by the
+    /// denomination, e.g. `0.25000000 BTC`.
+    fn ser_denominated<S: Serializer>(self, s: S) -> Result<S::Ok, S::Error>;
+    /// Deserializes the amount from a string followed by its denomination,
+    /// e.g. `0.25 BTC` or `150000 sat`.
+    fn des_denominated<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error>;
+}
+
+mod private {
+    use {Denomination, FromBtcError, ParseAmountError};
+
+    /// Prevents `SerdeAmount` from being implemented outside this crate.
+    pub trait Sealed: Sized {
+        /// Converts an integer number of satoshis, `None` if it doesn't fit
+        /// in the amount type or, with `strict-money`, isn't valid money.
+        fn from_u64_sat(sat: u64) -> Option<Self>;
+        /// Converts an integer number of satoshis, `None` if it doesn't fit
+        /// in the amount type or, with `strict-money`, isn't valid money.
+        fn from_i64_sat(sat: i64) -> Option<Self>;
+        /// Parses an integer number of satoshis.
+        fn parse_sat(s: &str) -> Result<Self, ParseAmountError>;
+        /// Parses a decimal number of `denom` units exactly.
+        fn parse_in(s: &str, denom: Denomination) -> Result<Self, ParseAmountError>;
+        /// Parses a decimal number followed by its denomination exactly.
+        fn parse_denominated(s: &str) -> Result<Self, ParseAmountError>;
+        /// Converts a floating point number of BTC, rounded to the nearest
+        /// satoshi.
+        fn from_btc_f64(btc: f64) -> Result<Self, FromBtcError>;
+    }
+}
+
+macro_rules! impl_serde_amount {
+    ($ty:ident, $inner:ty) => {
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                if s.is_human_readable() {
+                    self.ser_str(s)
+                } else {
+                    self.ser_sat(s)
+                }
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                if d.is_human_readable() {
+                    $ty::des_str(d)
+                } else {
+                    $ty::des_sat(d)
+                }
+            }
+        }
+
+        impl private::Sealed for $ty {
+            fn from_u64_sat(sat: u64) -> Option<Self> {
+                <$inner>::try_from(sat).ok().and_then(|sat| $ty::try_from_sat(sat).ok())
+            }
+
+            fn from_i64_sat(sat: i64) -> Option<Self> {
+                <$inner>::try_from(sat).ok().and_then(|sat| $ty::try_from_sat(sat).ok())
+            }
+
+            fn parse_sat(s: &str) -> Result<Self, ParseAmountError> {
+                $ty::from_sat_str(s)
+            }
+
+            fn parse_in(s: &str, denom: Denomination) -> Result<Self, ParseAmountError> {
+                $ty::from_str_in(s, denom)
+            }
+
+            fn parse_denominated(s: &str) -> Result<Self, ParseAmountError> {
+                $ty::from_str_with_denomination(s)
+            }
+
+            fn from_btc_f64(btc: f64) -> Result<Self, FromBtcError> {
+                $ty::try_from_btc(btc)
+            }
+        }
+
+        impl SerdeAmount for $ty {
+            fn ser_sat<S: Serializer>(self, s: S) -> Result<S::Ok, S::Error> {
+                <$inner>::serialize(&self.into_inner(), s)
+            }
+
+            fn des_sat<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                let sat = <$inner>::deserialize(d)?;
+                $ty::try_from_sat(sat).map_err(|_| de::Error::custom(ParseAmountError::OutOfRange))
+            }
+
+            fn ser_sat_str<S: Serializer>(self, s: S) -> Result<S::Ok, S::Error> {
+                s.collect_str(&self.into_inner())
+            }
+
+            fn des_sat_str<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                d.deserialize_str(SatStrVisitor(PhantomData))
+            }
+
+            fn des_sat_lenient<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                d.deserialize_any(SatLenientVisitor(PhantomData))
+            }
+
+            fn ser_btc<S: Serializer>(self, s: S) -> Result<S::Ok, S::Error> {
+                s.serialize_f64(self.to_btc())
+            }
+
+            fn des_btc<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                // `serde_json` only hands out the decimal text of numbers
+                // through `deserialize_any`, see `BtcVisitor::visit_map`.
+                if cfg!(feature = "serde_json") && d.is_human_readable() {
+                    d.deserialize_any(BtcVisitor(PhantomData))
+                } else {
+                    d.deserialize_f64(BtcVisitor(PhantomData))
+                }
+            }
+
+            #[cfg(feature = "serde_json")]
+            fn ser_btc_json<S: Serializer>(self, s: S) -> Result<S::Ok, S::Error> {
+                self.to_serde_json().serialize(s)
+            }
+
+            fn ser_str<S: Serializer>(self, s: S) -> Result<S::Ok, S::Error> {
+                s.collect_str(&self)
+            }
+
+            fn des_str<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                d.deserialize_str(StrVisitor(PhantomData))
+            }
+
+            fn des_lenient<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                d.deserialize_any(LenientVisitor(PhantomData))
+            }
+
+            fn ser_tagged_sat<S: Serializer>(self, s: S) -> Result<S::Ok, S::Error> {
+                let mut map = s.serialize_map(Some(1))?;
+                map.serialize_entry("sat", &Repr::<_, Sat>(self, PhantomData))?;
+                map.end()
+            }
+
+            fn ser_tagged_btc<S: Serializer>(self, s: S) -> Result<S::Ok, S::Error> {
+                let mut map = s.serialize_map(Some(1))?;
+                map.serialize_entry("btc", &Repr::<_, Str>(self, PhantomData))?;
+                map.end()
+            }
+
+            fn des_tagged<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                d.deserialize_map(TaggedVisitor(PhantomData))
+            }
+
+            fn ser_str_in<S: Serializer>(self, denom: Denomination, s: S) -> Result<S::Ok, S::Error> {
+                s.collect_str(&self.display_in(denom))
+            }
+
+            fn des_str_in<'de, D: Deserializer<'de>>(denom: Denomination, d: D) -> Result<Self, D::Error> {
+                d.deserialize_str(StrInVisitor(denom, PhantomData))
+            }
+
+            fn ser_denominated<S: Serializer>(self, s: S) -> Result<S::Ok, S::Error> {
+                s.collect_str(&self.display_in(Denomination::Bitcoin).show_denomination(true))
+            }
+
+            fn des_denominated<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                d.deserialize_str(DenominatedVisitor(PhantomData))
+            }
+        }
+    };
+}
+
+impl_serde_amount!(Amount, u64);
+impl_serde_amount!(SignedAmount, i64);
+
+/// Matches the decimal BTC strings of `Amount`s.
+#[cfg(any(feature = "schemars", feature = "utoipa"))]
+const BTC_PATTERN: &str = r"^[0-9]+(\.[0-9]{1,8})?$";
+/// Matches the decimal BTC strings of `SignedAmount`s.
+#[cfg(any(feature = "schemars", feature = "utoipa"))]
+const SIGNED_BTC_PATTERN: &str = r"^-?[0-9]+(\.[0-9]{1,8})?$";
+/// Describes the decimal BTC strings of the default representation.
+#[cfg(any(feature = "schemars", feature = "utoipa"))]
+const BTC_DESCRIPTION: &str = "A decimal number of BTC, e.g. \"0.00253583\".";
+
+/// Describes the decimal BTC string JSON uses, see `Serialize`.
+#[cfg(feature = "schemars")]
+macro_rules! impl_json_schema {
+    ($ty:ident, $pattern:expr) => {
+        impl JsonSchema for $ty {
+            fn schema_name() -> Cow<'static, str> {
+                stringify!($ty).into()
+            }
+
+            fn schema_id() -> Cow<'static, str> {
+                concat!("bitcoin_amount::", stringify!($ty)).into()
+            }
+
+            fn json_schema(_: &mut SchemaGenerator) -> Schema {
+                json_schema!({
+                    "type": "string",
+                    "pattern": $pattern,
+                    "description": BTC_DESCRIPTION
+                })
+            }
+        }
+    };
+}
+
+#[cfg(feature = "schemars")]
+impl_json_schema!(Amount, BTC_PATTERN);
+#[cfg(feature = "schemars")]
+impl_json_schema!(SignedAmount, SIGNED_BTC_PATTERN);
+
+/// Describes the decimal BTC string JSON uses for OpenAPI, see `Serialize`.
+#[cfg(feature = "utoipa")]
+macro_rules! impl_to_schema {
+    ($ty:ident, $pattern:expr, $example:expr) => {
+        impl ::utoipa::PartialSchema for $ty {
+            fn schema() -> RefOr<openapi::Schema> {
+                ObjectBuilder::new()
+                    .schema_type(Type::String)
+                    .pattern(Some($pattern))
+                    .description(Some(BTC_DESCRIPTION))
+                    .examples(Some($example))
+                    .into()
+            }
+        }
+
+        impl ::utoipa::ToSchema for $ty {
+            fn name() -> Cow<'static, str> {
+                stringify!($ty).into()
+            }
+        }
+    };
+}
+
+#[cfg(feature = "utoipa")]
+impl_to_schema!(Amount, BTC_PATTERN, "0.00253583");
+#[cfg(feature = "utoipa")]
+impl_to_schema!(SignedAmount, SIGNED_BTC_PATTERN, "-0.00253583");
+
+/// Marks `Repr` as (de)serializing with `ser_sat`/`des_sat`.
+struct Sat;
+/// Marks `Repr` as (de)serializing with `ser_str`/`des_str`.
+struct Str;
+
+/// Wraps an amount to (de)serialize it with the representation `R`, to be
+/// nested in other values.
+struct Repr<A, R>(A, PhantomData<R>);
+
+impl<A: SerdeAmount> Serialize for Repr<A, Sat> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        self.0.ser_sat(s)
+    }
+}
+
+impl<'de, A: SerdeAmount> Deserialize<'de> for Repr<A, Sat> {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        A::des_sat(d).map(|a| Repr(a, PhantomData))
+    }
+}
+
+impl<A: SerdeAmount> Serialize for Repr<A, Str> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        self.0.ser_str(s)
+    }
+}
+
+impl<'de, A: SerdeAmount> Deserialize<'de> for Repr<A, Str> {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        A::des_str(d).map(|a| Repr(a, PhantomData))
+    }
+}
+
+/// The unit field of a tagged amount object.
+enum Tag {
+    Sat,
+    Btc,
+}
+
+const TAGS: &[&str] = &["sat", "btc"];
+
+impl<'de> Deserialize<'de> for Tag {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        struct TagVisitor;
+
+        impl<'de> Visitor<'de> for TagVisitor {
+            type Value = Tag;
+
+            fn expecting(&self, fmt: &mut Formatter) -> fmt::Result {
+                fmt.write_str("`sat` or `btc`")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Tag, E> {
+                match v {
+                    "sat" => Ok(Tag::Sat),
+                    "btc" => Ok(Tag::Btc),
+                    _ => Err(E::unknown_field(v, TAGS)),
+                }
+            }
+        }
+
+        d.deserialize_identifier(TagVisitor)
+    }
+}
+
+/// Parses an amount from an object with a single `sat` or `btc` field.
+struct TaggedVisitor<A>(PhantomData<A>);
+
+impl<'de, A: SerdeAmount> Visitor<'de> for TaggedVisitor<A> {
+    type Value = A;
+
+    fn expecting(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.write_str("an object with a single `sat` or `btc` field")
+    }
+
+    fn visit_map<M: MapAccess<'de>>(self, mut map: M) -> Result<A, M::Error> {
+        let amount = match map.next_key()? {
+            Some(Tag::Sat) => map.next_value::<Repr<A, Sat>>()?.0,
+            Some(Tag::Btc) => map.next_value::<Repr<A, Str>>()?.0,
+            None => return Err(de::Error::invalid_length(0, &self)),
+        };
+        if map.next_key::<Tag>()?.is_some() {
+            return Err(de::Error::invalid_length(2, &self));
+        }
+        Ok(amount)
+    }
+}
+
+/// The JSON schema of the objects accepted by `TaggedVisitor`.
+#[cfg(feature = "schemars")]
+fn tagged_json_schema() -> Schema {
+    json_schema!({
+        "oneOf": [
+            {
+                "type": "object",
+                "properties": { "sat": { "type": "integer" } },
+                "required": ["sat"],
+                "additionalProperties": false
+            },
+            {
+                "type": "object",
+                "properties": { "btc": { "type": "string", "pattern": SIGNED_BTC_PATTERN } },
+                "required": ["btc"],
+                "additionalProperties": false
+            }
+        ]
+    })
+}
+
+/// Declares an `opt` module (de)serializing `Option`s of amounts with the
+/// `serialize` and `deserialize` functions of the parent module, `None`
+/// being `null`.
+macro_rules! opt_module {
+    () => {
+        /// (De)serializes an `Option` of an amount in the representation of
+        /// the parent module, `None` is `null`. Add `#[serde(default)]` to
+        /// the field to also accept a missing field as `None`.
+        pub mod opt {
+            use std::fmt::{self, Formatter};
+            use std::marker::PhantomData;
+
+            use serde_crate::de::{self, Deserializer, Visitor};
+            use serde_crate::{Serialize, Serializer};
+
+            use serde::SerdeAmount;
+
+            /// Serializes the wrapped amount with the parent's representation.
+            struct Wrapper<'a, A: 'a>(&'a A);
+
+            impl<'a, A: SerdeAmount> Serialize for Wrapper<'a, A> {
+                fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                    super::serialize(self.0, s)
+                }
+            }
+
+            struct OptVisitor<A>(PhantomData<A>);
+
+            impl<'de, A: SerdeAmount> Visitor<'de> for OptVisitor<A> {
+                type Value = Option<A>;
+
+                fn expecting(&self, fmt: &mut Formatter) -> fmt::Result {
+                    fmt.write_str("an optional amount")
+                }
+
+                fn visit_none<E: de::Error>(self) -> Result<Option<A>, E> {
+                    Ok(None)
+                }
+
+                fn visit_unit<E: de::Error>(self) -> Result<Option<A>, E> {
+                    Ok(None)
+                }
+
+                fn visit_some<D: Deserializer<'de>>(self, d: D) -> Result<Option<A>, D::Error> {
+                    super::deserialize(d).map(Some)
+                }
+            }
+
+            /// Serializes `amount`, or `null` if it's `None`.
+            pub fn serialize<A, S>(amount: &Option<A>, s: S) -> Result<S::Ok, S::Error>
+            where
+                A: SerdeAmount,
+                S: Serializer,
+            {
+                match *amount {
+                    Some(ref amount) => s.serialize_some(&Wrapper(amount)),
+                    None => s.serialize_none(),
+                }
+            }
+
+            /// Deserializes an amount, or `None` from `null`.
+            pub fn deserialize<'de, A, D>(d: D) -> Result<Option<A>, D::Error>
+            where
+                A: SerdeAmount,
+                D: Deserializer<'de>,
+            {
+                d.deserialize_option(OptVisitor(PhantomData))
+            }
+
+            /// The JSON schema of the parent module's representation, or
+            /// `null`.
+            #[cfg(feature = "schemars")]
+            pub fn json_schema(gen: &mut ::schemars::SchemaGenerator) -> ::schemars::Schema {
+                json_schema!({ "anyOf": [super::json_schema(gen), { "type": "null" }] })
+            }
+        }
+    };
+}
+
+/// Parses an amount from a string without allocating it.
+struct StrVisitor<A>(PhantomData<A>);
+
+impl<'de, A> Visitor<'de> for StrVisitor<A>
+where
+    A: FromStr<Err = ParseAmountError>,
+{
+    type Value = A;
+
+    fn expecting(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.write_str("a decimal BTC string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<A, E> {
+        A::from_str(v).map_err(E::custom)
+    }
+}
+
+/// Parses an amount from a string of satoshis.
+struct SatStrVisitor<A>(PhantomData<A>);
+
+impl<'de, A: private::Sealed> Visitor<'de> for SatStrVisitor<A> {
+    type Value = A;
+
+    fn expecting(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.write_str("a string of satoshis")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<A, E> {
+        A::parse_sat(v).map_err(E::custom)
+    }
+}
+
+/// Parses an amount from an integer number of satoshis or a string of them.
+struct SatLenientVisitor<A>(PhantomData<A>);
+
+impl<'de, A: private::Sealed> Visitor<'de> for SatLenientVisitor<A> {
+    type Value = A;
+
+    fn expecting(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.write_str("an integer number of satoshis or a string of them")
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<A, E> {
+        A::from_u64_sat(v).ok_or_else(|| E::invalid_value(Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<A, E> {
+        A::from_i64_sat(v).ok_or_else(|| E::invalid_value(Unexpected::Signed(v), &self))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<A, E> {
+        A::parse_sat(v).map_err(E::custom)
+    }
+}
+
+/// Parses an amount from a decimal string in a denomination.
+struct StrInVisitor<A>(Denomination, PhantomData<A>);
+
+impl<'de, A: private::Sealed> Visitor<'de> for StrInVisitor<A> {
+    type Value = A;
+
+    fn expecting(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(fmt, "a decimal {} string", self.0)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<A, E> {
+        A::parse_in(v, self.0).map_err(E::custom)
+    }
+}
+
+/// Parses an amount from a string followed by its denomination.
+struct DenominatedVisitor<A>(PhantomData<A>);
+
+impl<'de, A: private::Sealed> Visitor<'de> for DenominatedVisitor<A> {
+    type Value = A;
+
+    fn expecting(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.write_str("an amount with its denomination, e.g. \"0.25 BTC\"")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<A, E> {
+        A::parse_denominated(v).map_err(E::custom)
+    }
+}
+
+/// Parses an amount from a number of BTC, exactly when its decimal text is
+/// available.
+struct BtcVisitor<A>(PhantomData<A>);
+
+impl<A> BtcVisitor<A>
+where
+    A: FromStr<Err = ParseAmountError> + private::Sealed,
+{
+    /// Parses the decimal text of a number exactly, sub-satoshi precision
+    /// is rejected rather than rounded.
+    fn parse_exact<E: de::Error>(s: &str) -> Result<A, E> {
+        A::from_str(s).map_err(E::custom)
+    }
+}
+
+impl<'de, A> Visitor<'de> for BtcVisitor<A>
+where
+    A: FromStr<Err = ParseAmountError> + private::Sealed,
+{
+    type Value = A;
+
+    fn expecting(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.write_str("a number of BTC")
+    }
+
+    /// Whole BTC are converted directly, only the ones out of range are
+    /// formatted and parsed, for the same error as strings.
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<A, E> {
+        match v.checked_mul(SAT_PER_BTC as u64).and_then(A::from_u64_sat) {
+            Some(amount) => Ok(amount),
+            None => Self::parse_exact(&v.to_string()),
+        }
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<A, E> {
+        match v.checked_mul(SAT_PER_BTC).and_then(A::from_i64_sat) {
+            Some(amount) => Ok(amount),
+            None => Self::parse_exact(&v.to_string()),
+        }
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<A, E> {
+        A::from_btc_f64(v).map_err(E::custom)
+    }
+
+    /// `serde_json` with `arbitrary_precision` represents numbers as a map
+    /// holding their decimal text.
+    #[cfg(feature = "serde_json")]
+    fn visit_map<M: de::MapAccess<'de>>(self, map: M) -> Result<A, M::Error> {
+        use serde_crate::de::value::MapAccessDeserializer;
+
+        let number = ::serde_json::Number::deserialize(MapAccessDeserializer::new(map))?;
+        Self::parse_exact(number.as_str())
+    }
+}
+
+/// Parses an amount from satoshis, BTC or a string of either.
+struct LenientVisitor<A>(PhantomData<A>);
+
+impl<'de, A> Visitor<'de> for LenientVisitor<A>
+where
+    A: FromStr<Err = ParseAmountError> + private::Sealed,
+{
+    type Value = A;
+
+    fn expecting(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.write_str("an integer number of satoshis, a number of BTC or a string of either")
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<A, E> {
+        A::from_u64_sat(v).ok_or_else(|| E::invalid_value(Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<A, E> {
+        A::from_i64_sat(v).ok_or_else(|| E::invalid_value(Unexpected::Signed(v), &self))
+    }
+
+    /// The float is formatted with its shortest round-trip representation
+    /// and parsed exactly, so values with sub-satoshi precision are rejected
+    /// instead of rounded.
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<A, E> {
+        if !v.is_finite() {
+            return Err(E::invalid_value(Unexpected::Float(v), &self));
+        }
+        A::from_str(&v.to_string()).map_err(E::custom)
+    }
+
+    /// Strings with a decimal point are parsed as BTC, otherwise as
+    /// satoshis.
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<A, E> {
+        if v.contains('.') {
+            A::from_str(v).map_err(E::custom)
+        } else {
+            A::parse_sat(v).map_err(E::custom)
+        }
+    }
+
+    /// `serde_json` with `arbitrary_precision` represents floats, and
+    /// integers out of the `u64` and `i64` range, as a map holding their
+    /// decimal text, which is parsed exactly as BTC if it's a float and as
+    /// satoshis otherwise.
+    #[cfg(feature = "serde_json")]
+    fn visit_map<M: de::MapAccess<'de>>(self, map: M) -> Result<A, M::Error> {
+        use serde_crate::de::value::MapAccessDeserializer;
+
+        let number = ::serde_json::Number::deserialize(MapAccessDeserializer::new(map))?;
+        let s = number.as_str();
+        if s.contains(['.', 'e', 'E']) {
+            A::from_str(s).map_err(de::Error::custom)
+        } else {
+            A::parse_sat(s).map_err(de::Error::custom)
+        }
+    }
+}
+
+/// (De)serializes an amount as an integer number of satoshis, use with
+/// `#[serde(with = "bitcoin_amount::serde::as_sat")]`.
+pub mod as_sat {
+    use serde_crate::{Deserializer, Serializer};
+
+    use super::SerdeAmount;
+
+    /// Serializes `amount` as an integer number of satoshis.
+    pub fn serialize<A: SerdeAmount, S: Serializer>(amount: &A, s: S) -> Result<S::Ok, S::Error> {
+        amount.ser_sat(s)
+    }
+
+    /// Deserializes an amount from an integer number of satoshis.
+    pub fn deserialize<'de, A: SerdeAmount, D: Deserializer<'de>>(d: D) -> Result<A, D::Error> {
+        A::des_sat(d)
+    }
+
+    /// The JSON schema of an integer number of satoshis.
+    #[cfg(feature = "schemars")]
+    pub fn json_schema(_: &mut ::schemars::SchemaGenerator) -> ::schemars::Schema {
+        json_schema!({ "type": "integer" })
+    }
+
+    opt_module!();
+}
+
+/// (De)serializes an amount as a string of satoshis, e.g. `"253583"`, use
+/// with `#[serde(with = "bitcoin_amount::serde::as_sat_str")]`.
+pub mod as_sat_str {
+    use serde_crate::{Deserializer, Serializer};
+
+    use super::SerdeAmount;
+
+    /// Serializes `amount` as a string of satoshis.
+    pub fn serialize<A: SerdeAmount, S: Serializer>(amount: &A, s: S) -> Result<S::Ok, S::Error> {
+        amount.ser_sat_str(s)
+    }
+
+    /// Deserializes an amount from a string of satoshis.
+    pub fn deserialize<'de, A: SerdeAmount, D: Deserializer<'de>>(d: D) -> Result<A, D::Error> {
+        A::des_sat_str(d)
+    }
+
+    /// The JSON schema of a string of satoshis.
+    #[cfg(feature = "schemars")]
+    pub fn json_schema(_: &mut ::schemars::SchemaGenerator) -> ::schemars::Schema {
+        json_schema!({ "type": "string", "pattern": "^-?[0-9]+$" })
+    }
+
+    opt_module!();
+}
+
+/// Serializes an amount as an integer number of satoshis and deserializes it
+/// from either an integer or a string of satoshis, use with
+/// `#[serde(with = "bitcoin_amount::serde::as_sat_lenient")]`.
+///
+/// This is how Esplora, Electrum servers and most block explorers encode
+/// values, e.g. `2100` or `"2100"`. Unlike `lenient` nothing is ever read as
+/// BTC, `"0.5"` is rejected.
+pub mod as_sat_lenient {
+    use serde_crate::{Deserializer, Serializer};
+
+    use super::SerdeAmount;
+
+    /// Serializes `amount` as an integer number of satoshis.
+    pub fn serialize<A: SerdeAmount, S: Serializer>(amount: &A, s: S) -> Result<S::Ok, S::Error> {
+        amount.ser_sat(s)
+    }
+
+    /// Deserializes an amount from an integer number of satoshis or a string
+    /// of them.
+    pub fn deserialize<'de, A: SerdeAmount, D: Deserializer<'de>>(d: D) -> Result<A, D::Error> {
+        A::des_sat_lenient(d)
+    }
+
+    /// The JSON schema of an integer number of satoshis or a string of them.
+    #[cfg(feature = "schemars")]
+    pub fn json_schema(_: &mut ::schemars::SchemaGenerator) -> ::schemars::Schema {
+        json_schema!({ "anyOf": [{ "type": "integer" }, { "type": "string", "pattern": "^-?[0-9]+$" }] })
+    }
+
+    opt_module!();
+}
+
+/// (De)serializes an amount as a floating point number of BTC, use with
+/// `#[serde(with = "bitcoin_amount::serde::as_btc")]`.
+pub mod as_btc {
+    use serde_crate::{Deserializer, Serializer};
+
+    use super::SerdeAmount;
+
+    /// Serializes `amount` as a floating point number of BTC.
+    pub fn serialize<A: SerdeAmount, S: Serializer>(amount: &A, s: S) -> Result<S::Ok, S::Error> {
+        amount.ser_btc(s)
+    }
+
+    /// Deserializes an amount from a floating point number of BTC, rounded
+    /// to the nearest satoshi. JSON numbers are parsed exactly with the
+    /// `serde_json` feature, sub-satoshi precision is rejected.
+    pub fn deserialize<'de, A: SerdeAmount, D: Deserializer<'de>>(d: D) -> Result<A, D::Error> {
+        A::des_btc(d)
+    }
+
+    /// The JSON schema of a number of BTC.
+    #[cfg(feature = "schemars")]
+    pub fn json_schema(_: &mut ::schemars::SchemaGenerator) -> ::schemars::Schema {
+        json_schema!({ "type": "number" })
+    }
+
+    opt_module!();
+}
+
+/// (De)serializes an amount as a JSON number of BTC with exactly 8 decimal
+/// places, use with `#[serde(with = "bitcoin_amount::serde::as_btc_json")]`.
+///
+/// Unlike `as_btc` no `f64` is involved, the output is byte-compatible with
+/// Bitcoin Core's RPC. Only `serde_json` understands the numbers serialized
+/// by this module.
+#[cfg(feature = "serde_json")]
+pub mod as_btc_json {
+    use serde_crate::{Deserializer, Serializer};
+
+    use super::SerdeAmount;
+
+    /// Serializes `amount` as a JSON number of BTC with 8 decimal places.
+    pub fn serialize<A: SerdeAmount, S: Serializer>(amount: &A, s: S) -> Result<S::Ok, S::Error> {
+        amount.ser_btc_json(s)
+    }
+
+    /// Deserializes an amount from a JSON number of BTC, parsed exactly,
+    /// sub-satoshi precision is rejected.
+    pub fn deserialize<'de, A: SerdeAmount, D: Deserializer<'de>>(d: D) -> Result<A, D::Error> {
+        A::des_btc(d)
+    }
+
+    /// The JSON schema of a number of BTC.
+    #[cfg(feature = "schemars")]
+    pub fn json_schema(_: &mut ::schemars::SchemaGenerator) -> ::schemars::Schema {
+        json_schema!({ "type": "number" })
+    }
+
+    opt_module!();
+}
+
+/// (De)serializes an amount as a decimal BTC string, use with
+/// `#[serde(with = "bitcoin_amount::serde::as_str")]`.
+pub mod as_str {
+    use serde_crate::{Deserializer, Serializer};
+
+    use super::SerdeAmount;
+
+    /// Serializes `amount` as a decimal BTC string with 8 decimal places.
+    pub fn serialize<A: SerdeAmount, S: Serializer>(amount: &A, s: S) -> Result<S::Ok, S::Error> {
+        amount.ser_str(s)
+    }
+
+    /// Deserializes an amount from a decimal BTC string, parsed exactly.
+    pub fn deserialize<'de, A: SerdeAmount, D: Deserializer<'de>>(d: D) -> Result<A, D::Error> {
+        A::des_str(d)
+    }
+
+    /// The JSON schema of a decimal BTC string.
+    #[cfg(feature = "schemars")]
+    pub fn json_schema(_: &mut ::schemars::SchemaGenerator) -> ::schemars::Schema {
+        json_schema!({ "type": "string", "pattern": super::SIGNED_BTC_PATTERN })
+    }
+
+    opt_module!();
+}
+
+/// Deserializes an amount from an integer number of satoshis, a floating
+/// point number of BTC or a string holding either, use with
+/// `#[serde(with = "bitcoin_amount::serde::lenient")]`.
+///
+/// e.g. `2100`, `"2100"`, `0.000021` and `"0.000021"` are all 2100 satoshis.
+/// Floats are checked to be exact, `0.000000001` is rejected rather than
+/// rounded.
+pub mod lenient {
+    use serde_crate::{Deserializer, Serializer};
+
+    use super::SerdeAmount;
+
+    /// Serializes `amount` as an integer number of satoshis.
+    pub fn serialize<A: SerdeAmount, S: Serializer>(amount: &A, s: S) -> Result<S::Ok, S::Error> {
+        amount.ser_sat(s)
+    }
+
+    /// Deserializes an amount from an integer number of satoshis, a floating
+    /// point number of BTC or a string holding either.
+    pub fn deserialize<'de, A: SerdeAmount, D: Deserializer<'de>>(d: D) -> Result<A, D::Error> {
+        A::des_lenient(d)
+    }
+
+    /// The JSON schema of a number of satoshis or BTC, or a string of either.
+    #[cfg(feature = "schemars")]
+    pub fn json_schema(_: &mut ::schemars::SchemaGenerator) -> ::schemars::Schema {
+        json_schema!({ "type": ["number", "string"] })
+    }
+
+    opt_module!();
+}
+
+/// Declares a module of `as_str_in` for the denomination `denom`.
+macro_rules! str_in_module {
+    ($name:ident, $denom:ident, $example:expr) => {
+        #[doc = "(De)serializes an amount as a decimal string in "]
+        #[doc = stringify!($denom)]
+        #[doc = ", e.g. `"]
+        #[doc = $example]
+        #[doc = "`."]
+        pub mod $name {
+            use serde_crate::{Deserializer, Serializer};
+
+            use serde::SerdeAmount;
+            use Denomination;
+
+            /// Serializes `amount` as a decimal string without the
+            /// denomination suffix.
+            pub fn serialize<A, S>(amount: &A, s: S) -> Result<S::Ok, S::Error>
+            where
+                A: SerdeAmount,
+                S: Serializer,
+            {
+                amount.ser_str_in(Denomination::$denom, s)
+            }
+
+            /// Deserializes an amount from a decimal string, parsed exactly.
+            pub fn deserialize<'de, A, D>(d: D) -> Result<A, D::Error>
+            where
+                A: SerdeAmount,
+                D: Deserializer<'de>,
+            {
+                A::des_str_in(Denomination::$denom, d)
+            }
+
+            /// The JSON schema of a decimal string.
+            #[cfg(feature = "schemars")]
+            pub fn json_schema(_: &mut ::schemars::SchemaGenerator) -> ::schemars::Schema {
+                json_schema!({ "type": "string", "pattern": r"^-?[0-9]+(\.[0-9]+)?$" })
+            }
+
+            opt_module!();
+        }
+    };
+}
+
+/// (De)serializes an amount as a decimal string in a chosen denomination,
+/// without the denomination suffix, e.g. with
+/// `#[serde(with = "bitcoin_amount::serde::as_str_in::mbtc")]`.
+///
+/// Meant for CSV and other flat formats, the strings have as many decimal
+/// places as needed to represent a satoshi and are parsed back exactly.
+pub mod as_str_in {
+    str_in_module!(btc, Bitcoin, "0.00253583");
+    str_in_module!(mbtc, MilliBitcoin, "2.53583");
+    str_in_module!(ubtc, MicroBitcoin, "2535.83");
+    str_in_module!(bits, Bit, "2535.83");
+    str_in_module!(sat, Satoshi, "253583");
+    str_in_module!(msat, MilliSatoshi, "253583000");
+}
+
+/// (De)serializes an amount as a string with its denomination, use with
+/// `#[serde(with = "bitcoin_amount::serde::with_denomination")]`.
+///
+/// Meant for configuration files, e.g. a `max_fee = "150000 sat"` TOML key.
+/// Any denomination is accepted, case-insensitively, and digits can be
+/// grouped with `_`, see `Amount::from_str_with_denomination`. Amounts are
+/// serialized in BTC, e.g. `"0.25000000 BTC"`.
+pub mod with_denomination {
+    use serde_crate::{Deserializer, Serializer};
+
+    use super::SerdeAmount;
+
+    /// Serializes `amount` as a decimal BTC string followed by `BTC`.
+    pub fn serialize<A: SerdeAmount, S: Serializer>(amount: &A, s: S) -> Result<S::Ok, S::Error> {
+        amount.ser_denominated(s)
+    }
+
+    /// Deserializes an amount from a decimal string followed by its
+    /// denomination, parsed exactly.
+    pub fn deserialize<'de, A: SerdeAmount, D: Deserializer<'de>>(d: D) -> Result<A, D::Error> {
+        A::des_denominated(d)
+    }
+
+    /// The JSON schema of a decimal string followed by its denomination.
+    #[cfg(feature = "schemars")]
+    pub fn json_schema(_: &mut ::schemars::SchemaGenerator) -> ::schemars::Schema {
+        json_schema!({ "type": "string" })
+    }
+
+    opt_module!();
+}
+
+/// (De)serializes an amount as a `{"sat": 253583}` object, use with
+/// `#[serde(with = "bitcoin_amount::serde::as_tagged_sat")]`.
+///
+/// Deserializing also accepts the `{"btc": "0.00253583"}` objects of
+/// `as_tagged_btc`.
+pub mod as_tagged_sat {
+    use serde_crate::{Deserializer, Serializer};
+
+    use super::SerdeAmount;
+
+    /// Serializes `amount` as a `{"sat": 253583}` object.
+    pub fn serialize<A: SerdeAmount, S: Serializer>(amount: &A, s: S) -> Result<S::Ok, S::Error> {
+        amount.ser_tagged_sat(s)
+    }
+
+    /// Deserializes an amount from a `{"sat": 253583}` or a
+    /// `{"btc": "0.00253583"}` object.
+    pub fn deserialize<'de, A: SerdeAmount, D: Deserializer<'de>>(d: D) -> Result<A, D::Error> {
+        A::des_tagged(d)
+    }
+
+    /// The JSON schema of a `{"sat": 253583}` or `{"btc": "0.00253583"}` object.
+    #[cfg(feature = "schemars")]
+    pub fn json_schema(_: &mut ::schemars::SchemaGenerator) -> ::schemars::Schema {
+        super::tagged_json_schema()
+    }
+
+    opt_module!();
+}
+
+/// (De)serializes an amount as a `{"btc": "0.00253583"}` object, use with
+/// `#[serde(with = "bitcoin_amount::serde::as_tagged_btc")]`.
+///
+/// Deserializing also accepts the `{"sat": 253583}` objects of
+/// `as_tagged_sat`.
+pub mod as_tagged_btc {
+    use serde_crate::{Deserializer, Serializer};
+
+    use super::SerdeAmount;
+
+    /// Serializes `amount` as a `{"btc": "0.00253583"}` object.
+    pub fn serialize<A: SerdeAmount, S: Serializer>(amount: &A, s: S) -> Result<S::Ok, S::Error> {
+        amount.ser_tagged_btc(s)
+    }
+
+    /// Deserializes an amount from a `{"sat": 253583}` or a
+    /// `{"btc": "0.00253583"}` object.
+    pub fn deserialize<'de, A: SerdeAmount, D: Deserializer<'de>>(d: D) -> Result<A, D::Error> {
+        A::des_tagged(d)
+    }
+
+    /// The JSON schema of a `{"sat": 253583}` or `{"btc": "0.00253583"}` object.
+    #[cfg(feature = "schemars")]
+    pub fn json_schema(_: &mut ::schemars::SchemaGenerator) -> ::schemars::Schema {
+        super::tagged_json_schema()
+    }
+
+    opt_module!();
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_test::{
+        assert_de_tokens, assert_de_tokens_error, assert_tokens, Compact, Configure, Readable, Token,
+    };
+
+    use {Amount, SignedAmount};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(crate = "serde_crate")]
+    struct Amounts {
+        #[serde(with = "::serde::as_sat")]
+        sat: Amount,
+        #[serde(with = "::serde::as_btc")]
+        btc: SignedAmount,
+        #[serde(with = "::serde::as_str")]
+        string: Amount,
+    }
+
+    #[test]
+    fn serde_human_readable() {
+        let amount = Amount::from_sat(253583);
+        assert_tokens(&amount.readable(), &[Token::Str("0.00253583")]);
+        assert_tokens(&amount.compact(), &[Token::U64(253583)]);
+
+        let amount = SignedAmount::from_sat(-253583);
+        assert_tokens(&amount.readable(), &[Token::Str("-0.00253583")]);
+        assert_tokens(&amount.compact(), &[Token::I64(-253583)]);
+
+        assert_de_tokens_error::<Readable<Amount>>(
+            &[Token::U64(253583)],
+            "invalid type: integer `253583`, expected a decimal BTC string",
+        );
+    }
+
+    #[test]
+    fn serde_with_modules() {
+        let amounts = Amounts {
+            sat: Amount::from_sat(253583),
+            btc: SignedAmount::from_sat(-253583),
+            string: Amount::from_sat(253583),
+        };
+
+        assert_tokens(&amounts.compact(), &[
+            Token::Struct { name: "Amounts", len: 3 },
+            Token::Str("sat"),
+            Token::U64(253583),
+            Token::Str("btc"),
+            Token::F64(-0.00253583),
+            Token::Str("string"),
+            Token::Str("0.00253583"),
+            Token::StructEnd,
+        ]);
+    }
+
+    #[test]
+    fn serde_with_modules_errors() {
+        assert_de_tokens_error::<Compact<Amounts>>(&[
+            Token::Struct { name: "Amounts", len: 3 },
+            Token::Str("sat"),
+            Token::U64(1),
+            Token::Str("btc"),
+            Token::F64(f64::NAN),
+        ], "BTC value is not a number");
+
+        assert_de_tokens_error::<Compact<Amounts>>(&[
+            Token::Struct { name: "Amounts", len: 3 },
+            Token::Str("sat"),
+            Token::U64(1),
+            Token::Str("btc"),
+            Token::F64(1.0),
+            Token::Str("string"),
+            Token::Str("0.000000001"),
+        ], "amount has more than 8 decimal places");
+    }
+
+    #[test]
+    fn serde_btc_integers() {
+        let tokens = |btc| [
+            Token::Struct { name: "Amounts", len: 3 },
+            Token::Str("sat"),
+            Token::U64(1),
+            Token::Str("btc"),
+            btc,
+            Token::Str("string"),
+            Token::Str("1"),
+            Token::StructEnd,
+        ];
+        let amounts = |btc| Amounts {
+            sat: Amount::from_sat(1),
+            btc: SignedAmount::from_sat(btc),
+            string: Amount::from_sat(100_000_000),
+        };
+
+        assert_de_tokens(&amounts(2_100_000_000).compact(), &tokens(Token::U64(21)));
+        assert_de_tokens(&amounts(-2_100_000_000).compact(), &tokens(Token::I64(-21)));
+        assert_de_tokens_error::<Compact<Amounts>>(&tokens(Token::U64(u64::MAX))[..5],
+                                                   "amount out of range");
+        assert_de_tokens_error::<Compact<Amounts>>(&tokens(Token::I64(i64::MIN))[..5],
+                                                   "amount out of range");
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(crate = "serde_crate")]
+    struct Lenient {
+        #[serde(with = "::serde::lenient")]
+        amount: SignedAmount,
+    }
+
+    fn lenient(value: Token) -> [Token; 4] {
+        [
+            Token::Struct { name: "Lenient", len: 1 },
+            Token::Str("amount"),
+            value,
+            Token::StructEnd,
+        ]
+    }
+
+    #[test]
+    fn serde_lenient() {
+        let expected = Lenient { amount: SignedAmount::from_sat(2100) };
+        assert_de_tokens(&expected, &lenient(Token::U64(2100)));
+        assert_de_tokens(&expected, &lenient(Token::I64(2100)));
+        assert_de_tokens(&expected, &lenient(Token::F64(0.000021)));
+        assert_de_tokens(&expected, &lenient(Token::Str("2100")));
+        assert_de_tokens(&expected, &lenient(Token::Str("0.000021")));
+
+        let expected = Lenient { amount: SignedAmount::from_sat(-2100) };
+        assert_de_tokens(&expected, &lenient(Token::I64(-2100)));
+        assert_de_tokens(&expected, &lenient(Token::F64(-0.000021)));
+        assert_de_tokens(&expected, &lenient(Token::Str("-2100")));
+    }
+
+    #[test]
+    fn serde_lenient_errors() {
+        assert_de_tokens_error::<Lenient>(
+            &lenient(Token::F64(0.000000001)),
+            "amount has more than 8 decimal places",
+        );
+        assert_de_tokens_error::<Lenient>(
+            &lenient(Token::F64(f64::INFINITY)),
+            "invalid value: floating point `inf`, expected an integer number of satoshis, a \
+             number of BTC or a string of either",
+        );
+        assert_de_tokens_error::<Lenient>(
+            &lenient(Token::U64(u64::MAX)),
+            "invalid value: integer `18446744073709551615`, expected an integer number of \
+             satoshis, a number of BTC or a string of either",
+        );
+        assert_de_tokens_error::<Lenient>(
+            &lenient(Token::Str("0.5 BTC")),
+            "invalid character at position 3",
+        );
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(crate = "serde_crate")]
+    struct Utxo {
+        #[serde(with = "::serde::as_sat_lenient")]
+        value: Amount,
+        #[serde(with = "::serde::as_sat_str")]
+        fee: SignedAmount,
+    }
+
+    #[test]
+    fn serde_sat_modules() {
+        let utxo = Utxo { value: Amount::from_sat(253583), fee: SignedAmount::from_sat(-141) };
+        assert_tokens(&utxo, &[
+            Token::Struct { name: "Utxo", len: 2 },
+            Token::Str("value"),
+            Token::U64(253583),
+            Token::Str("fee"),
+            Token::Str("-141"),
+            Token::StructEnd,
+        ]);
+        assert_de_tokens(&utxo, &[
+            Token::Struct { name: "Utxo", len: 2 },
+            Token::Str("value"),
+            Token::Str("253583"),
+            Token::Str("fee"),
+            Token::Str("-141"),
+            Token::StructEnd,
+        ]);
+
+        assert_de_tokens_error::<Utxo>(&[
+            Token::Struct { name: "Utxo", len: 2 },
+            Token::Str("value"),
+            Token::F64(0.5),
+        ], "invalid type: floating point `0.5`, expected an integer number of satoshis or a string of them");
+        assert_de_tokens_error::<Utxo>(&[
+            Token::Struct { name: "Utxo", len: 2 },
+            Token::Str("value"),
+            Token::I64(-1),
+        ], "invalid value: integer `-1`, expected an integer number of satoshis or a string of them");
+        assert_de_tokens_error::<Utxo>(&[
+            Token::Struct { name: "Utxo", len: 2 },
+            Token::Str("value"),
+            Token::U64(1),
+            Token::Str("fee"),
+            Token::I64(141),
+        ], "invalid type: integer `141`, expected a string of satoshis");
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(crate = "serde_crate")]
+    struct OptAmounts {
+        #[serde(with = "::serde::as_btc::opt")]
+        fee: Option<Amount>,
+        #[serde(default, with = "::serde::as_sat::opt")]
+        missing: Option<SignedAmount>,
+    }
+
+    #[test]
+    fn serde_opt_modules() {
+        let amounts = OptAmounts {
+            fee: Some(Amount::from_sat(253583)),
+            missing: None,
+        };
+        assert_tokens(&amounts.compact(), &[
+            Token::Struct { name: "OptAmounts", len: 2 },
+            Token::Str("fee"),
+            Token::Some,
+            Token::F64(0.00253583),
+            Token::Str("missing"),
+            Token::None,
+            Token::StructEnd,
+        ]);
+
+        let amounts = OptAmounts { fee: None, missing: None };
+        assert_de_tokens(&amounts.compact(), &[
+            Token::Struct { name: "OptAmounts", len: 1 },
+            Token::Str("fee"),
+            Token::Unit,
+            Token::StructEnd,
+        ]);
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    #[serde(crate = "serde_crate")]
+    struct Tagged {
+        #[serde(with = "::serde::as_tagged_sat")]
+        sat: Amount,
+        #[serde(with = "::serde::as_tagged_btc")]
+        btc: SignedAmount,
+    }
+
+    #[test]
+    fn serde_tagged() {
+        let tagged = Tagged {
+            sat: Amount::from_sat(253583),
+            btc: SignedAmount::from_sat(-253583),
+        };
+        assert_tokens(&tagged.compact(), &[
+            Token::Struct { name: "Tagged", len: 2 },
+            Token::Str("sat"),
+            Token::Map { len: Some(1) },
+            Token::Str("sat"),
+            Token::U64(253583),
+            Token::MapEnd,
+            Token::Str("btc"),
+            Token::Map { len: Some(1) },
+            Token::Str("btc"),
+            Token::Str("-0.00253583"),
+            Token::MapEnd,
+            Token::StructEnd,
+        ]);
+
+        // Either unit is accepted.
+        assert_de_tokens(&tagged.compact(), &[
+            Token::Struct { name: "Tagged", len: 2 },
+            Token::Str("sat"),
+            Token::Map { len: Some(1) },
+            Token::Str("btc"),
+            Token::Str("0.00253583"),
+            Token::MapEnd,
+            Token::Str("btc"),
+            Token::Map { len: Some(1) },
+            Token::Str("sat"),
+            Token::I64(-253583),
+            Token::MapEnd,
+            Token::StructEnd,
+        ]);
+    }
+
+    #[test]
+    fn serde_tagged_errors() {
+        let tagged = |tokens: &[Token]| {
+            let mut all = vec![Token::Struct { name: "Tagged", len: 2 }, Token::Str("sat")];
+            all.extend_from_slice(tokens);
+            all
+        };
+        assert_de_tokens_error::<Compact<Tagged>>(
+            &tagged(&[Token::Map { len: Some(1) }, Token::Str("msat"), Token::U64(1)]),
+            "unknown field `msat`, expected `sat` or `btc`",
+        );
+        assert_de_tokens_error::<Compact<Tagged>>(
+            &tagged(&[Token::Map { len: Some(0) }, Token::MapEnd]),
+            "invalid length 0, expected an object with a single `sat` or `btc` field",
+        );
+        assert_de_tokens_error::<Compact<Tagged>>(
+            &tagged(&[
+                Token::Map { len: Some(2) },
+                Token::Str("sat"),
+                Token::U64(1),
+                Token::Str("btc"),
+            ]),
+            "invalid length 2, expected an object with a single `sat` or `btc` field",
+        );
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(crate = "serde_crate")]
+    struct Limits {
+        #[serde(with = "::serde::with_denomination")]
+        max_fee: Amount,
+        #[serde(with = "::serde::with_denomination::opt", default)]
+        min_change: Option<SignedAmount>,
+    }
+
+    #[test]
+    fn serde_with_denomination() {
+        let limits = Limits { max_fee: Amount::from_sat(25_000_000), min_change: None };
+        assert_tokens(&limits, &[
+            Token::Struct { name: "Limits", len: 2 },
+            Token::Str("max_fee"),
+            Token::Str("0.25000000 BTC"),
+            Token::Str("min_change"),
+            Token::None,
+            Token::StructEnd,
+        ]);
+
+        assert_de_tokens(&Limits {
+            max_fee: Amount::from_sat(150_000),
+            min_change: Some(SignedAmount::from_sat(-150_000)),
+        }, &[
+            Token::Struct { name: "Limits", len: 2 },
+            Token::Str("max_fee"),
+            Token::Str("150_000 sat"),
+            Token::Str("min_change"),
+            Token::Some,
+            Token::Str("-1.5 mBTC"),
+            Token::StructEnd,
+        ]);
+        assert_de_tokens(&limits, &[
+            Token::Struct { name: "Limits", len: 1 },
+            Token::Str("max_fee"),
+            Token::Str("0.25 btc"),
+            Token::StructEnd,
+        ]);
+    }
+
+    #[test]
+    fn serde_with_denomination_errors() {
+        let limits = |token| [
+            Token::Struct { name: "Limits", len: 1 },
+            Token::Str("max_fee"),
+            token,
+            Token::StructEnd,
+        ];
+        assert_de_tokens_error::<Limits>(&limits(Token::Str("0.25")), "unknown denomination: ");
+        assert_de_tokens_error::<Limits>(&limits(Token::Str("-1 sat")), "unexpected negative amount");
+        assert_de_tokens_error::<Limits>(
+            &limits(Token::U64(150000)),
+            "invalid type: integer `150000`, expected an amount with its denomination, e.g. \
+             \"0.25 BTC\"",
+        );
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(crate = "serde_crate")]
+    struct Row {
+        id: u32,
+        #[serde(with = "::serde::as_str_in::btc")]
+        amount: Amount,
+        #[serde(with = "::serde::as_str_in::sat")]
+        fee: SignedAmount,
+        #[serde(with = "::serde::as_str_in::mbtc::opt")]
+        change: Option<Amount>,
+    }
+
+    #[test]
+    fn serde_csv_str_in() {
+        let rows = vec![
+            Row {
+                id: 1,
+                amount: Amount::from_sat(253583),
+                fee: SignedAmount::from_sat(-141),
+                change: Some(Amount::from_sat(100_000_001)),
+            },
+            Row { id: 2, amount: Amount::zero(), fee: SignedAmount::zero(), change: None },
+        ];
+        let csv = "id,amount,fee,change\n1,0.00253583,-141,1000.00001\n2,0.00000000,0,\n";
+
+        let mut writer = ::csv::Writer::from_writer(vec![]);
+        for row in &rows {
+            writer.serialize(row).unwrap();
+        }
+        assert_eq!(String::from_utf8(writer.into_inner().unwrap()).unwrap(), csv);
+
+        let mut reader = ::csv::Reader::from_reader(csv.as_bytes());
+        let read = reader.deserialize().collect::<Result<Vec<Row>, _>>().unwrap();
+        assert_eq!(read, rows);
+
+        let mut reader = ::csv::Reader::from_reader("id,amount,fee,change\n1,0.1,0.5,\n".as_bytes());
+        let err = reader.deserialize::<Row>().next().unwrap().unwrap_err();
+        assert!(err.to_string().contains("amount has more than 8 decimal places"), "{}", err);
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn serde_json_lenient() {
+        let parse = |s: &str| ::serde_json::from_str::<Lenient>(s).map(|l| l.amount.into_inner());
+        assert_eq!(parse(r#"{"amount": 2100}"#).unwrap(), 2100);
+        assert_eq!(parse(r#"{"amount": -2100}"#).unwrap(), -2100);
+        assert_eq!(parse(r#"{"amount": 0.000021}"#).unwrap(), 2100);
+        assert_eq!(parse(r#"{"amount": -0.000021}"#).unwrap(), -2100);
+        assert_eq!(parse(r#"{"amount": 2.1e-5}"#).unwrap(), 2100);
+        assert_eq!(parse(r#"{"amount": -0}"#).unwrap(), 0);
+        assert_eq!(parse(r#"{"amount": "2100"}"#).unwrap(), 2100);
+        assert_eq!(parse(r#"{"amount": "0.000021"}"#).unwrap(), 2100);
+        let err = parse(r#"{"amount": 0.000000001}"#).unwrap_err();
+        assert!(err.to_string().contains("amount has more than 8 decimal places"), "{}", err);
+        let err = parse(r#"{"amount": 100000000000000000000}"#).unwrap_err();
+        assert!(err.to_string().contains("amount out of range"), "{}", err);
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn serde_json_exact_btc() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        #[serde(crate = "serde_crate")]
+        struct Btc {
+            #[serde(with = "::serde::as_btc")]
+            btc: SignedAmount,
+        }
+
+        let parse = |s: &str| ::serde_json::from_str::<Btc>(s).map(|b| b.btc.into_inner());
+        // Above 2^53 satoshis an `f64` would be off by one.
+        #[cfg(feature = "strict-money")]
+        assert!(parse(r#"{"btc": 92233720.36854775}"#).is_err());
+        #[cfg(not(feature = "strict-money"))]
+        assert_eq!(parse(r#"{"btc": 92233720.36854775}"#).unwrap(), 9223372036854775);
+        assert_eq!(parse(r#"{"btc": 20999999.99999999}"#).unwrap(), 2099999999999999);
+        assert_eq!(parse(r#"{"btc": -0.29999999}"#).unwrap(), -29999999);
+        assert_eq!(parse(r#"{"btc": 21}"#).unwrap(), 2100000000);
+        assert_eq!(parse(r#"{"btc": 1e-8}"#).unwrap(), 1);
+        let err = parse(r#"{"btc": 0.000000016}"#).unwrap_err();
+        assert!(err.to_string().contains("amount has more than 8 decimal places"), "{}", err);
+        assert!(parse(r#"{"btc": "0.1"}"#).is_err());
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn serde_json_btc_json() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        #[serde(crate = "serde_crate")]
+        struct Btc {
+            #[serde(with = "::serde::as_btc_json")]
+            btc: Amount,
+            #[serde(with = "::serde::as_btc_json::opt")]
+            fee: Option<SignedAmount>,
+        }
+
+        let btc = Btc {
+            btc: Amount::from_sat(2099999999999999),
+            fee: Some(SignedAmount::from_sat(-1000)),
+        };
+        let json = r#"{"btc":20999999.99999999,"fee":-0.00001000}"#;
+        assert_eq!(::serde_json::to_string(&btc).unwrap(), json);
+        assert_eq!(::serde_json::from_str::<Btc>(json).unwrap(), btc);
+
+        let btc = Btc { btc: Amount::from_sat(100000000), fee: None };
+        assert_eq!(::serde_json::to_string(&btc).unwrap(), r#"{"btc":1.00000000,"fee":null}"#);
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn json_schemas() {
+        use schemars::{JsonSchema, SchemaGenerator};
+
+        let mut gen = SchemaGenerator::default();
+        assert_eq!(Amount::json_schema(&mut gen).get("pattern"),
+                   Some(&r"^[0-9]+(\.[0-9]{1,8})?$".into()));
+        assert_eq!(SignedAmount::json_schema(&mut gen).get("pattern"),
+                   Some(&r"^-?[0-9]+(\.[0-9]{1,8})?$".into()));
+        assert_eq!(::serde::as_sat::json_schema(&mut gen), json_schema!({ "type": "integer" }));
+        assert_eq!(::serde::as_btc::opt::json_schema(&mut gen), json_schema!({
+            "anyOf": [{ "type": "number" }, { "type": "null" }]
+        }));
+        assert_eq!(::serde::lenient::json_schema(&mut gen),
+                   json_schema!({ "type": ["number", "string"] }));
+        let tagged = ::serde::as_tagged_btc::json_schema(&mut gen);
+        assert_eq!(tagged.get("oneOf").and_then(|v| v.as_array()).map(Vec::len), Some(2));
+    }
+
+    #[cfg(feature = "utoipa")]
+    #[test]
+    fn utoipa_schemas() {
+        use utoipa::openapi::{RefOr, Schema};
+        use utoipa::{PartialSchema, ToSchema};
+
+        assert_eq!(Amount::name(), "Amount");
+        match SignedAmount::schema() {
+            RefOr::T(Schema::Object(ref object)) => {
+                assert_eq!(object.pattern.as_deref(),
+                           Some(r"^-?[0-9]+(\.[0-9]{1,8})?$"));
+            }
+            _ => panic!("unexpected schema"),
+        }
+    }
+}
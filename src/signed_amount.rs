@@ -0,0 +1,395 @@
+//! Signed bitcoin amounts.
+
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::iter::Sum;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
+
+use {div_rounded, Amount, CheckedSum, IntoBtc, NegativeAmountError, OutOfRangeError, Rounding};
+#[cfg(feature = "std")]
+use {try_round_to_sat, FromBtcError};
+
+/// A signed bitcoin amount integer type.
+///
+/// A negative amount represents a debit and can be obtained by negating a
+/// credit with the unary `-` operator.
+///
+/// Signed amounts can be added to and subtracted from each other, multiplied
+/// and divided by an `i64` scalar, and divided by another signed amount to
+/// get the (truncated) ratio between them.
+///
+/// # Overflow
+///
+/// The arithmetic operators (`+`, `-`, `*`, `/` and `%`) always panic on
+/// overflow, including negating `min_value()`, and on division by zero,
+/// regardless of whether the crate was compiled in debug or release mode,
+/// money values are never silently wrapped. Use the `checked_*`,
+/// `saturating_*`, `overflowing_*` or `wrapping_*` methods to handle
+/// overflow explicitly.
+#[derive(Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "rkyv", derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize))]
+#[cfg_attr(feature = "rkyv", rkyv(derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)))]
+#[cfg_attr(feature = "diesel", derive(::diesel::expression::AsExpression, ::diesel::deserialize::FromSqlRow))]
+#[cfg_attr(feature = "diesel", diesel(sql_type = ::diesel::sql_types::BigInt))]
+pub struct SignedAmount(i64);
+
+impl_amount!(SignedAmount, i64);
+
+#[cfg(feature = "rkyv")]
+impl ArchivedSignedAmount {
+    /// Reads the archived amount without deserializing it.
+    pub fn to_native(&self) -> SignedAmount {
+        SignedAmount(self.0.to_native())
+    }
+}
+
+impl SignedAmount {
+    /// Creates a `SignedAmount` from the given type.
+    pub fn from_btc<T>(btc: T) -> SignedAmount
+    where T:
+          IntoBtc,
+    {
+        btc.into_btc()
+    }
+
+    /// Creates a `SignedAmount` from a floating point BTC value.
+    ///
+    /// Returns an error if `btc` is NaN, infinite or its satoshi equivalent
+    /// doesn't fit in a `SignedAmount`, or with the `strict-money` feature
+    /// enabled, isn't valid money.
+    #[cfg(feature = "std")]
+    pub fn try_from_btc(btc: f64) -> Result<SignedAmount, FromBtcError> {
+        let sat = try_round_to_sat(btc)?;
+        if !(-9_223_372_036_854_775_808.0..9_223_372_036_854_775_808.0).contains(&sat) {
+            return Err(FromBtcError::OutOfRange);
+        }
+
+        SignedAmount::try_from_sat(sat as i64).map_err(|_| FromBtcError::OutOfRange)
+    }
+
+    /// Converts this amount to an unsigned `Amount`, returns an error if it's
+    /// negative.
+    pub fn to_unsigned(self) -> Result<Amount, NegativeAmountError> {
+        u64::try_from(self.0).map(Amount).map_err(|_| NegativeAmountError(()))
+    }
+
+    /// Returns `true` if this amount is negative, i.e. a debit.
+    pub const fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    /// Returns the absolute value of this amount.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is `min_value()`, use `unsigned_abs` instead.
+    pub fn abs(self) -> SignedAmount {
+        self.checked_abs().expect("SignedAmount abs overflow")
+    }
+
+    /// Checked absolute value. Returns `None` if `self` is `min_value()`.
+    pub const fn checked_abs(self) -> Option<SignedAmount> {
+        match self.0.checked_abs() {
+            Some(sat) => Some(SignedAmount(sat)),
+            None => None,
+        }
+    }
+
+    /// Returns the absolute value of this amount as an unsigned `Amount`,
+    /// this can't overflow.
+    pub const fn unsigned_abs(self) -> Amount {
+        Amount(self.0.unsigned_abs())
+    }
+
+    /// Returns a number representing the sign of this amount: `-1` if it's
+    /// negative, `0` if it's zero and `1` if it's positive.
+    pub const fn signum(self) -> i64 {
+        self.0.signum()
+    }
+
+    /// Checked negation. Returns `None` if `self` is `min_value()`.
+    pub const fn checked_neg(self) -> Option<SignedAmount> {
+        match self.0.checked_neg() {
+            Some(sat) => Some(SignedAmount(sat)),
+            None => None,
+        }
+    }
+
+    /// Computes the absolute difference between `self` and `other`.
+    ///
+    /// The difference is computed without intermediate overflow, so amounts
+    /// of opposite sign can be compared safely.
+    pub const fn abs_diff(self, other: SignedAmount) -> Amount {
+        Amount(self.0.abs_diff(other.0))
+    }
+}
+
+impl Neg for SignedAmount {
+    type Output = SignedAmount;
+
+    fn neg(self) -> Self::Output {
+        self.checked_neg().expect("SignedAmount negation overflow")
+    }
+}
+
+impl From<i32> for SignedAmount {
+    fn from(sat: i32) -> SignedAmount {
+        SignedAmount::from_sat(i64::from(sat))
+    }
+}
+
+impl From<u32> for SignedAmount {
+    fn from(sat: u32) -> SignedAmount {
+        SignedAmount::from_sat(i64::from(sat))
+    }
+}
+
+impl From<i64> for SignedAmount {
+    fn from(sat: i64) -> SignedAmount {
+        SignedAmount::from_sat(sat)
+    }
+}
+
+impl TryFrom<u64> for SignedAmount {
+    type Error = OutOfRangeError;
+
+    fn try_from(sat: u64) -> Result<SignedAmount, OutOfRangeError> {
+        i64::try_from(sat).map_err(|_| OutOfRangeError(())).and_then(SignedAmount::try_from_sat)
+    }
+}
+
+impl TryFrom<i128> for SignedAmount {
+    type Error = OutOfRangeError;
+
+    fn try_from(sat: i128) -> Result<SignedAmount, OutOfRangeError> {
+        i64::try_from(sat).map_err(|_| OutOfRangeError(())).and_then(SignedAmount::try_from_sat)
+    }
+}
+
+impl From<SignedAmount> for i64 {
+    fn from(amt: SignedAmount) -> i64 {
+        amt.0
+    }
+}
+
+impl From<SignedAmount> for i128 {
+    fn from(amt: SignedAmount) -> i128 {
+        i128::from(amt.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use ParseAmountError;
+
+    fn sat(sat: i64) -> SignedAmount {
+        SignedAmount::from_sat(sat)
+    }
+
+    #[test]
+    fn signed_amount_from_btc_str() {
+        assert_eq!(SignedAmount::from_btc(-0.00253583), sat(-253583));
+        assert_eq!(SignedAmount::from_str("-0.10000000").unwrap(), sat(-10_000_000));
+        assert_eq!(SignedAmount::from_str("0.10000000").unwrap(), sat(10_000_000));
+    }
+
+    #[test]
+    fn signed_amount_from_str_exact() {
+        assert_eq!(SignedAmount::from_str("-20999999.99999999").unwrap(), sat(-2_099_999_999_999_999));
+        assert_eq!(SignedAmount::from_str("92233720368.54775808"), Err(ParseAmountError::OutOfRange));
+        assert_eq!(SignedAmount::from_str("-0.000000015"), Err(ParseAmountError::TooPrecise));
+        assert_eq!(SignedAmount::from_str("-1e-5"), Ok(sat(-1_000)));
+    }
+
+    #[test]
+    fn signed_amount_from_str_bounds() {
+        let min = SignedAmount::from_str("-92233720368.54775808");
+        let max = SignedAmount::from_str("92233720368.54775807");
+        #[cfg(feature = "strict-money")]
+        assert_eq!((min, max), (Err(ParseAmountError::OutOfRange), Err(ParseAmountError::OutOfRange)));
+        #[cfg(not(feature = "strict-money"))]
+        assert_eq!((min, max), (Ok(SignedAmount::min_value()), Ok(SignedAmount::max_value())));
+    }
+
+    #[test]
+    fn signed_amount_checked_ops() {
+        let (max, min, one) = (SignedAmount::max_value(), SignedAmount::min_value(), sat(1));
+        assert_eq!(max.checked_add(one), None);
+        assert_eq!(min.checked_sub(one), None);
+        assert_eq!(max.checked_mul(2), None);
+        assert_eq!(one.checked_div(0), None);
+        assert_eq!(min.checked_div(-1), None);
+        assert_eq!(min.checked_rem(-1), None);
+
+        assert_eq!(sat(5).checked_sub(sat(6)), Some(sat(-1)));
+        assert_eq!(sat(5).checked_mul(-3), Some(sat(-15)));
+        assert_eq!(sat(-7).checked_div(2), Some(sat(-3)));
+    }
+
+    #[test]
+    fn signed_amount_saturating_overflowing_wrapping_ops() {
+        let (max, min, one) = (SignedAmount::max_value(), SignedAmount::min_value(), sat(1));
+        assert_eq!(min.saturating_add(sat(-1)), min);
+        assert_eq!(max.saturating_sub(sat(-1)), max);
+        assert_eq!(max.saturating_mul(-2), min);
+
+        assert_eq!(max.overflowing_add(one), (min, true));
+        assert_eq!(min.overflowing_sub(one), (max, true));
+        assert_eq!(max.overflowing_mul(2), (sat(-2), true));
+
+        assert_eq!(max.wrapping_add(one), min);
+        assert_eq!(min.wrapping_sub(one), max);
+        assert_eq!(max.wrapping_mul(2), sat(-2));
+    }
+
+    #[test]
+    fn signed_amount_neg() {
+        let amt = sat(253583);
+        assert_eq!(-amt, sat(-253583));
+        assert_eq!(-(-amt), amt);
+        assert!((-amt).is_negative());
+        assert!(!amt.is_negative());
+        assert!(!SignedAmount::zero().is_negative());
+        assert_eq!(-SignedAmount::max_value(), SignedAmount(-i64::MAX));
+    }
+
+    #[test]
+    #[should_panic(expected = "SignedAmount negation overflow")]
+    fn signed_amount_neg_min_panics() {
+        let _ = -SignedAmount::min_value();
+    }
+
+    #[test]
+    #[should_panic(expected = "SignedAmount subtraction overflow")]
+    fn signed_amount_sub_overflow_panics() {
+        let _ = SignedAmount::min_value() - sat(1);
+    }
+
+    #[test]
+    fn signed_amount_abs_diff() {
+        let (a, b) = (sat(100), sat(-250));
+        assert_eq!(a.abs_diff(b), Amount::from_sat(350));
+        assert_eq!(b.abs_diff(a), Amount::from_sat(350));
+        assert_eq!(a.abs_diff(a), Amount::zero());
+        assert_eq!(SignedAmount::max_value().abs_diff(SignedAmount::min_value()),
+                   Amount::max_value());
+    }
+
+    #[test]
+    fn signed_amount_div_rounding() {
+        let (amt, neg) = (sat(10), sat(-10));
+        assert_eq!(neg.div_floor(3), sat(-4));
+        assert_eq!(neg.div_ceil(3), sat(-3));
+        assert_eq!(neg.div_round(4), sat(-3));
+        assert_eq!(amt.div_floor(-3), sat(-4));
+        assert_eq!(amt.div_ceil(-3), sat(-3));
+        assert_eq!(neg.div_round(-4), sat(3));
+
+        let max = SignedAmount::max_value();
+        assert_eq!(max.div_round(i64::MAX), sat(1));
+        assert_eq!(max.div_round(i64::MIN), sat(-1));
+    }
+
+    #[test]
+    fn signed_amount_midpoint_mean() {
+        let (max, min) = (SignedAmount::max_value(), SignedAmount::min_value());
+        assert_eq!(SignedAmount::midpoint(sat(-3), sat(0)), sat(-1));
+        assert_eq!(min.midpoint(max), sat(0));
+        assert_eq!(SignedAmount::mean(&[min, min]), Some(min));
+        assert_eq!(SignedAmount::mean(&[sat(-1), sat(-2), sat(-4)]), Some(sat(-2)));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn signed_amount_split() {
+        assert_eq!(sat(-10).split_evenly(4), vec![sat(-3), sat(-3), sat(-2), sat(-2)]);
+        assert_eq!(sat(-10).split_weighted(&[1, 2]), vec![sat(-3), sat(-7)]);
+
+        let parts = SignedAmount::min_value().split_weighted(&[u64::MAX, 1, u64::MAX]);
+        assert_eq!(parts.iter().checked_sum(), Some(SignedAmount::min_value()));
+    }
+
+    #[test]
+    fn signed_amount_to_unsigned() {
+        assert_eq!(sat(5).to_unsigned(), Ok(Amount::from_sat(5)));
+        assert_eq!(sat(0).to_unsigned(), Ok(Amount::zero()));
+        assert_eq!(sat(-1).to_unsigned(), Err(NegativeAmountError(())));
+        assert_eq!(SignedAmount::max_value().to_unsigned(), Ok(Amount(i64::MAX as u64)));
+    }
+
+    #[test]
+    fn signed_amount_unsigned_conversions() {
+        assert_eq!(Amount::try_from(sat(5)), Ok(Amount::from_sat(5)));
+        assert!(Amount::try_from(sat(-5)).is_err());
+        assert_eq!(SignedAmount::try_from(Amount::from_sat(5)), Ok(sat(5)));
+        assert!(SignedAmount::try_from(Amount::max_value()).is_err());
+    }
+
+    #[test]
+    fn signed_amount_max_money() {
+        let max_money = SignedAmount::MAX_MONEY;
+        assert!(max_money.is_valid_money());
+        assert!((-max_money).is_valid_money());
+        assert!(!(-max_money).wrapping_sub(sat(1)).is_valid_money());
+        assert!(!SignedAmount::min_value().is_valid_money());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn signed_amount_try_from_btc() {
+        assert_eq!(SignedAmount::try_from_btc(-0.00253583), Ok(sat(-253583)));
+        assert_eq!(SignedAmount::try_from_btc(f64::NAN), Err(FromBtcError::NotANumber));
+        assert_eq!(SignedAmount::try_from_btc(f64::NEG_INFINITY), Err(FromBtcError::Infinite));
+        assert_eq!(SignedAmount::try_from_btc(-1e11), Err(FromBtcError::OutOfRange));
+        assert_eq!(SignedAmount::try_from_btc(1e11), Err(FromBtcError::OutOfRange));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn signed_amount_try_from_btc_beyond_max_money() {
+        #[cfg(feature = "strict-money")]
+        assert_eq!(SignedAmount::try_from_btc(9e10), Err(FromBtcError::OutOfRange));
+        #[cfg(not(feature = "strict-money"))]
+        assert!(SignedAmount::try_from_btc(9e10).is_ok());
+    }
+
+    #[test]
+    fn signed_amount_sign_helpers() {
+        let (max, min) = (SignedAmount::max_value(), SignedAmount::min_value());
+        assert_eq!(sat(-5).abs(), sat(5));
+        assert_eq!(sat(5).abs(), sat(5));
+        assert_eq!(min.checked_abs(), None);
+        assert_eq!(sat(-5).checked_abs(), Some(sat(5)));
+        assert_eq!(min.unsigned_abs(), Amount(1 << 63));
+        assert_eq!(sat(-5).unsigned_abs(), Amount::from_sat(5));
+        assert_eq!(sat(-5).signum(), -1);
+        assert_eq!(sat(0).signum(), 0);
+        assert_eq!(sat(5).signum(), 1);
+        assert_eq!(min.checked_neg(), None);
+        assert_eq!(max.checked_neg(), Some(SignedAmount(-i64::MAX)));
+    }
+
+    #[test]
+    #[should_panic(expected = "SignedAmount abs overflow")]
+    fn signed_amount_abs_min_panics() {
+        let _ = SignedAmount::min_value().abs();
+    }
+
+    #[test]
+    fn signed_amount_int_conversions() {
+        assert_eq!(SignedAmount::from(-546i32), sat(-546));
+        assert_eq!(SignedAmount::from(546u32), sat(546));
+        assert_eq!(SignedAmount::from(-546i64), sat(-546));
+        assert_eq!(SignedAmount::try_from(546u64), Ok(sat(546)));
+        assert!(SignedAmount::try_from(u64::MAX).is_err());
+        assert_eq!(SignedAmount::try_from(-546i128), Ok(sat(-546)));
+        assert!(SignedAmount::try_from(i128::from(i64::MIN) - 1).is_err());
+
+        assert_eq!(i64::from(sat(-546)), -546);
+        assert_eq!(i128::from(sat(-546)), -546);
+    }
+}
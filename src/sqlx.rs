@@ -0,0 +1,241 @@
+//! `sqlx` mappings of amounts.
+//!
+//! `Amount` and `SignedAmount` map to BIGINT columns of satoshis on every
+//! database that supports `i64`, e.g. PostgreSQL, MySQL or SQLite.
+//! `Amount`s above `i64::MAX` satoshis can't be bound, and negative values
+//! can't be read as `Amount`s.
+//!
+//! With the `sqlx-postgres` feature `NumericAmount` and
+//! `NumericSignedAmount` wrap them to map to a PostgreSQL `NUMERIC(16, 8)`
+//! number of BTC instead, e.g. for columns shared with other applications.
+
+use std::convert::TryFrom;
+
+use sqlx_crate::encode::IsNull;
+use sqlx_crate::error::BoxDynError;
+use sqlx_crate::{Database, Decode, Encode, Type};
+#[cfg(feature = "sqlx-postgres")]
+use sqlx_crate::postgres::types::Oid;
+#[cfg(feature = "sqlx-postgres")]
+use sqlx_crate::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+
+use {Amount, SignedAmount};
+#[cfg(feature = "sqlx-postgres")]
+use ParseAmountError;
+
+macro_rules! impl_sqlx_bigint {
+    ($ty:ident) => {
+        impl<DB: Database> Type<DB> for $ty
+        where
+            i64: Type<DB>,
+        {
+            fn type_info() -> DB::TypeInfo {
+                <i64 as Type<DB>>::type_info()
+            }
+
+            fn compatible(ty: &DB::TypeInfo) -> bool {
+                <i64 as Type<DB>>::compatible(ty)
+            }
+        }
+
+        /// Binds a number of satoshis.
+        impl<'q, DB: Database> Encode<'q, DB> for $ty
+        where
+            i64: Encode<'q, DB>,
+        {
+            fn encode_by_ref(&self, buf: &mut DB::ArgumentBuffer) -> Result<IsNull, BoxDynError> {
+                i64::try_from(*self)?.encode(buf)
+            }
+        }
+
+        /// Reads a number of satoshis.
+        impl<'r, DB: Database> Decode<'r, DB> for $ty
+        where
+            i64: Decode<'r, DB>,
+        {
+            fn decode(value: DB::ValueRef<'r>) -> Result<$ty, BoxDynError> {
+                Ok($ty::try_from(i64::decode(value)?)?)
+            }
+        }
+    };
+}
+
+impl_sqlx_bigint!(Amount);
+impl_sqlx_bigint!(SignedAmount);
+
+/// The OID of the built-in `NUMERIC` type.
+#[cfg(feature = "sqlx-postgres")]
+const NUMERIC_OID: u32 = 1700;
+/// The sign of a positive binary `NUMERIC`.
+#[cfg(feature = "sqlx-postgres")]
+const NUMERIC_POS: u16 = 0x0000;
+/// The sign of a negative binary `NUMERIC`.
+#[cfg(feature = "sqlx-postgres")]
+const NUMERIC_NEG: u16 = 0x4000;
+
+/// Writes `sat` satoshis as a binary `NUMERIC` number of BTC with a scale of
+/// 8, in base 10000 digits.
+#[cfg(feature = "sqlx-postgres")]
+fn encode_numeric(sat: i128, buf: &mut Vec<u8>) {
+    let (sign, mut int) = if sat < 0 { (NUMERIC_NEG, -sat) } else { (NUMERIC_POS, sat) };
+    let frac = int % 100_000_000;
+    int /= 100_000_000;
+
+    // Digits from the least significant one, with a weight of -2.
+    let mut digits = vec![(frac % 10_000) as i16, (frac / 10_000) as i16];
+    while int > 0 {
+        digits.push((int % 10_000) as i16);
+        int /= 10_000;
+    }
+    let mut weight = digits.len() as i16 - 3;
+    while digits.last() == Some(&0) {
+        digits.pop();
+        weight -= 1;
+    }
+    let trailing = digits.iter().take_while(|&&d| d == 0).count();
+    digits.drain(..trailing);
+    if digits.is_empty() {
+        weight = 0;
+    }
+
+    buf.extend(&(digits.len() as i16).to_be_bytes());
+    buf.extend(&weight.to_be_bytes());
+    buf.extend(&sign.to_be_bytes());
+    buf.extend(&8i16.to_be_bytes());
+    for digit in digits.iter().rev() {
+        buf.extend(&digit.to_be_bytes());
+    }
+}
+
+/// Reads a binary `NUMERIC` number of BTC as satoshis, exactly.
+#[cfg(feature = "sqlx-postgres")]
+fn decode_numeric(buf: &[u8]) -> Result<i128, BoxDynError> {
+    let read = |i: usize| -> Result<i16, BoxDynError> {
+        buf.get(2 * i..2 * i + 2)
+            .map(|b| i16::from_be_bytes([b[0], b[1]]))
+            .ok_or_else(|| "truncated NUMERIC value".into())
+    };
+    let (len, weight, sign) = (read(0)?, read(1)?, read(2)? as u16);
+    let negative = match sign {
+        NUMERIC_POS => false,
+        NUMERIC_NEG => true,
+        _ => return Err(ParseAmountError::NotANumber.into()),
+    };
+
+    let mut sat = 0i128;
+    for i in 0..len {
+        let digit = i128::from(read(4 + i as usize)?);
+        // The power of ten of the digit in satoshis.
+        let exp = 4 * (i32::from(weight) - i32::from(i)) + 8;
+        if exp < 0 {
+            if digit != 0 {
+                return Err(ParseAmountError::TooPrecise.into());
+            }
+            continue;
+        }
+        sat = 10i128.checked_pow(exp as u32)
+            .and_then(|pow| digit.checked_mul(pow))
+            .and_then(|value| sat.checked_add(value))
+            .ok_or(ParseAmountError::OutOfRange)?;
+    }
+    Ok(if negative { -sat } else { sat })
+}
+
+#[cfg(feature = "sqlx-postgres")]
+macro_rules! impl_sqlx_numeric {
+    ($wrapper:ident, $ty:ident) => {
+        /// Wraps an amount to map it to a PostgreSQL `NUMERIC` number of
+        /// BTC, e.g. `0.00253583`, with a scale of 8.
+        #[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+        pub struct $wrapper(pub $ty);
+
+        impl Type<Postgres> for $wrapper {
+            fn type_info() -> PgTypeInfo {
+                PgTypeInfo::with_oid(Oid(NUMERIC_OID))
+            }
+        }
+
+        impl<'q> Encode<'q, Postgres> for $wrapper {
+            fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+                encode_numeric(i128::from(self.0), buf);
+                Ok(IsNull::No)
+            }
+        }
+
+        /// Reads a number of BTC exactly, sub-satoshi precision is rejected.
+        impl<'r> Decode<'r, Postgres> for $wrapper {
+            fn decode(value: PgValueRef<'r>) -> Result<$wrapper, BoxDynError> {
+                let amount = match value.format() {
+                    PgValueFormat::Binary => $ty::try_from(decode_numeric(value.as_bytes()?)?)?,
+                    PgValueFormat::Text => value.as_str()?.parse()?,
+                };
+                Ok($wrapper(amount))
+            }
+        }
+
+        impl From<$ty> for $wrapper {
+            fn from(amt: $ty) -> $wrapper {
+                $wrapper(amt)
+            }
+        }
+
+        impl From<$wrapper> for $ty {
+            fn from(wrapper: $wrapper) -> $ty {
+                wrapper.0
+            }
+        }
+    };
+}
+
+#[cfg(feature = "sqlx-postgres")]
+impl_sqlx_numeric!(NumericAmount, Amount);
+#[cfg(feature = "sqlx-postgres")]
+impl_sqlx_numeric!(NumericSignedAmount, SignedAmount);
+
+#[cfg(all(test, feature = "sqlx-postgres"))]
+mod tests {
+    use sqlx_crate::postgres::PgArguments;
+    use sqlx_crate::Arguments;
+
+    use super::{decode_numeric, encode_numeric};
+    use {Amount, ParseAmountError, SignedAmount};
+
+    #[test]
+    fn sqlx_bigint() {
+        let mut args = PgArguments::default();
+        assert!(args.add(Amount::from_sat(253583)).is_ok());
+        assert!(args.add(SignedAmount::from_sat(-1)).is_ok());
+        assert!(args.add(Amount::max_value()).is_err());
+    }
+
+    #[test]
+    fn sqlx_numeric() {
+        let numeric = |sat| {
+            let mut buf = vec![];
+            encode_numeric(sat, &mut buf);
+            buf.chunks(2).map(|b| i16::from_be_bytes([b[0], b[1]])).collect::<Vec<_>>()
+        };
+        // ndigits, weight, sign, dscale, digits...
+        assert_eq!(numeric(0), [0, 0, 0, 8]);
+        assert_eq!(numeric(253583), [2, -1, 0, 8, 25, 3583]);
+        assert_eq!(numeric(100_000_000), [1, 0, 0, 8, 1]);
+        assert_eq!(numeric(-2_100_000_000_000_001), [4, 1, 0x4000, 8, 2100, 0, 0, 1]);
+
+        let sats = [0, 1, 253583, 100_000_000, 2_100_000_000_000_001, -150_000, i128::from(u64::MAX)];
+        for &sat in &sats {
+            let mut buf = vec![];
+            encode_numeric(sat, &mut buf);
+            assert_eq!(decode_numeric(&buf).unwrap(), sat);
+        }
+
+        let decode = |words: &[i16]| {
+            let buf = words.iter().flat_map(|w| w.to_be_bytes()).collect::<Vec<_>>();
+            decode_numeric(&buf).map_err(|e| e.to_string())
+        };
+        assert_eq!(decode(&[1, -3, 0, 9, 0]), Ok(0));
+        assert_eq!(decode(&[1, -3, 0, 9, 5000]), Err(ParseAmountError::TooPrecise.to_string()));
+        assert_eq!(decode(&[0, 0, 0xC000u16 as i16, 0]), Err(ParseAmountError::NotANumber.to_string()));
+        assert_eq!(decode(&[1, 100, 0, 0, 1]), Err(ParseAmountError::OutOfRange.to_string()));
+        assert_eq!(decode(&[2, 0, 0, 8, 1]), Err("truncated NUMERIC value".to_owned()));
+    }
+}
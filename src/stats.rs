@@ -0,0 +1,112 @@
+//! Statistics over slices of amounts.
+//!
+//! All the functions return `None` for an empty slice and accumulate without
+//! risk of overflow.
+
+use alloc::vec::Vec;
+
+use {Amount, CheckedSum};
+
+/// Sums all of the `amounts`, returns `None` if the sum overflowed.
+pub fn sum_checked(amounts: &[Amount]) -> Option<Amount> {
+    amounts.iter().checked_sum()
+}
+
+/// Calculates the arithmetic mean of `amounts`, rounding towards zero.
+pub fn mean(amounts: &[Amount]) -> Option<Amount> {
+    Amount::mean(amounts)
+}
+
+/// Calculates the median of `amounts`. For an even number of amounts this is
+/// the midpoint of the two middle ones, rounding towards zero.
+pub fn median(amounts: &[Amount]) -> Option<Amount> {
+    if amounts.is_empty() {
+        return None;
+    }
+
+    let sorted = sorted(amounts);
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        Some(sorted[mid - 1].midpoint(sorted[mid]))
+    } else {
+        Some(sorted[mid])
+    }
+}
+
+/// Returns the smallest of `amounts`.
+pub fn min(amounts: &[Amount]) -> Option<Amount> {
+    amounts.iter().cloned().min()
+}
+
+/// Returns the largest of `amounts`.
+pub fn max(amounts: &[Amount]) -> Option<Amount> {
+    amounts.iter().cloned().max()
+}
+
+/// Calculates the `pct`-th percentile of `amounts` using the nearest-rank
+/// method, i.e. the smallest amount that is greater than or equal to `pct`
+/// percent of the amounts.
+///
+/// # Panics
+///
+/// Panics if `pct` is greater than 100.
+pub fn percentile(amounts: &[Amount], pct: u8) -> Option<Amount> {
+    assert!(pct <= 100, "percentile out of range");
+
+    if amounts.is_empty() {
+        return None;
+    }
+
+    let sorted = sorted(amounts);
+    let rank = (pct as usize * sorted.len()).div_ceil(100);
+    Some(sorted[rank.saturating_sub(1)])
+}
+
+fn sorted(amounts: &[Amount]) -> Vec<Amount> {
+    let mut sorted = amounts.to_vec();
+    sorted.sort();
+    sorted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use MAX;
+
+    fn sats(values: &[u64]) -> Vec<Amount> {
+        values.iter().map(|&v| Amount::from_sat(v)).collect()
+    }
+
+    #[test]
+    fn stats_empty() {
+        assert_eq!(sum_checked(&[]), Some(Amount::zero()));
+        assert_eq!(mean(&[]), None);
+        assert_eq!(median(&[]), None);
+        assert_eq!(min(&[]), None);
+        assert_eq!(max(&[]), None);
+        assert_eq!(percentile(&[], 50), None);
+    }
+
+    #[test]
+    fn stats_values() {
+        let amounts = sats(&[700, 100, 300, 500, 900]);
+        assert_eq!(sum_checked(&amounts), Some(Amount::from_sat(2500)));
+        assert_eq!(mean(&amounts), Some(Amount::from_sat(500)));
+        assert_eq!(median(&amounts), Some(Amount::from_sat(500)));
+        assert_eq!(median(&amounts[..4]), Some(Amount::from_sat(400)));
+        assert_eq!(min(&amounts), Some(Amount::from_sat(100)));
+        assert_eq!(max(&amounts), Some(Amount::from_sat(900)));
+        assert_eq!(percentile(&amounts, 0), Some(Amount::from_sat(100)));
+        assert_eq!(percentile(&amounts, 40), Some(Amount::from_sat(300)));
+        assert_eq!(percentile(&amounts, 90), Some(Amount::from_sat(900)));
+        assert_eq!(percentile(&amounts, 100), Some(Amount::from_sat(900)));
+    }
+
+    #[test]
+    fn stats_overflow() {
+        let amounts = [MAX, MAX];
+        assert_eq!(sum_checked(&amounts), None);
+        assert_eq!(mean(&amounts), Some(MAX));
+        assert_eq!(median(&amounts), Some(MAX));
+    }
+}
@@ -0,0 +1,82 @@
+//! The block subsidy schedule.
+//!
+//! The subsidy starts at 50 BTC and halves every 210,000 blocks by shifting
+//! the satoshis right, which truncates, until it reaches zero after 33
+//! halvings. Everything here is computed with the same integer math as
+//! Bitcoin Core's `GetBlockSubsidy`.
+
+use Amount;
+
+/// The number of blocks between halvings.
+pub const HALVING_INTERVAL: u32 = 210_000;
+
+/// The subsidy of the blocks of the first epoch, 50 BTC.
+pub const INITIAL_SUBSIDY: Amount = Amount::from_sat(50 * 100_000_000);
+
+/// Returns the halving epoch of the block at `height`, i.e. the number of
+/// halvings that happened before it.
+pub fn halving_epoch(height: u32) -> u32 {
+    height / HALVING_INTERVAL
+}
+
+/// Returns the subsidy of the block at `height`.
+pub fn block_subsidy(height: u32) -> Amount {
+    epoch_subsidy(halving_epoch(height))
+}
+
+/// Returns the total subsidy of the blocks from the genesis up to and
+/// including `height`.
+///
+/// The genesis block output can't be spent, it is counted anyway like the
+/// supply of the schedule does.
+pub fn supply_at_height(height: u32) -> Amount {
+    let epoch = halving_epoch(height);
+    let past: u64 = (0..epoch.min(64))
+        .map(|e| u64::from(HALVING_INTERVAL) * epoch_subsidy(e).into_inner())
+        .sum();
+    let blocks = u64::from(height % HALVING_INTERVAL) + 1;
+    Amount::from_sat(past + blocks * epoch_subsidy(epoch).into_inner())
+}
+
+fn epoch_subsidy(epoch: u32) -> Amount {
+    // Shifting by 64 or more bits would overflow, the subsidy is long gone
+    // by then anyway.
+    if epoch >= 64 {
+        return Amount::zero();
+    }
+    Amount::from_sat(INITIAL_SUBSIDY.into_inner() >> epoch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subsidy_schedule() {
+        assert_eq!(block_subsidy(0), Amount::from_sat(5_000_000_000));
+        assert_eq!(block_subsidy(209_999), Amount::from_sat(5_000_000_000));
+        assert_eq!(block_subsidy(210_000), Amount::from_sat(2_500_000_000));
+        assert_eq!(block_subsidy(840_000), Amount::from_sat(312_500_000));
+        assert_eq!(block_subsidy(32 * 210_000), Amount::from_sat(1));
+        assert_eq!(block_subsidy(33 * 210_000), Amount::zero());
+        assert_eq!(block_subsidy(u32::MAX), Amount::zero());
+
+        assert_eq!(halving_epoch(0), 0);
+        assert_eq!(halving_epoch(630_000), 3);
+        assert_eq!(halving_epoch(u32::MAX), 20_452);
+    }
+
+    #[test]
+    fn subsidy_supply() {
+        assert_eq!(supply_at_height(0), Amount::from_sat(5_000_000_000));
+        assert_eq!(supply_at_height(209_999), Amount::from_sat(1_050_000_000_000_000));
+        assert_eq!(supply_at_height(210_000), Amount::from_sat(1_050_002_500_000_000));
+
+        // The well known final supply, slightly below `MAX_MONEY` because of
+        // the truncating shifts.
+        let supply = Amount::from_sat(2_099_999_997_690_000);
+        assert_eq!(supply_at_height(33 * 210_000 - 1), supply);
+        assert_eq!(supply_at_height(u32::MAX), supply);
+        assert!(supply < Amount::MAX_MONEY);
+    }
+}
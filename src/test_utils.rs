@@ -0,0 +1,139 @@
+//! Fixtures for the tests of crates using amounts.
+//!
+//! Edge case amounts, a deterministic sequence of amounts and golden parse
+//! and format vectors, the same ones this crate checks itself against, so
+//! downstream tests don't each write their own.
+
+use {Amount, Denomination};
+
+/// One satoshi, the smallest non-zero amount.
+pub const ONE_SAT: Amount = Amount(1);
+
+/// One BTC.
+pub const ONE_BTC: Amount = Amount(100_000_000);
+
+/// The dust limit of P2PKH outputs at the default dust relay fee.
+pub const DUST_P2PKH: Amount = Amount(546);
+
+/// The dust limit of P2SH outputs at the default dust relay fee.
+pub const DUST_P2SH: Amount = Amount(540);
+
+/// The dust limit of P2WPKH outputs at the default dust relay fee.
+pub const DUST_P2WPKH: Amount = Amount(294);
+
+/// The dust limit of P2WSH outputs at the default dust relay fee.
+pub const DUST_P2WSH: Amount = Amount(330);
+
+/// The dust limit of P2TR outputs at the default dust relay fee.
+pub const DUST_P2TR: Amount = Amount(330);
+
+/// Amounts at the boundaries that tend to break amount handling, all within
+/// the valid money range.
+pub static EDGE_CASES: &[Amount] = &[
+    Amount(0),
+    ONE_SAT,
+    Amount(99),
+    Amount(100),
+    DUST_P2WPKH,
+    DUST_P2TR,
+    DUST_P2SH,
+    DUST_P2PKH,
+    Amount(99_999_999),
+    ONE_BTC,
+    Amount(100_000_001),
+    Amount(2_099_999_997_690_000),
+    Amount::MAX_MONEY,
+];
+
+/// BTC strings and the satoshis they parse to with `FromStr`, `None` if they
+/// must be rejected.
+pub static PARSE_VECTORS: &[(&str, Option<u64>)] = &[
+    ("0", Some(0)),
+    ("1", Some(100_000_000)),
+    ("0.00000001", Some(1)),
+    ("0.1", Some(10_000_000)),
+    (".5", Some(50_000_000)),
+    ("1.", Some(100_000_000)),
+    ("21000000", Some(2_100_000_000_000_000)),
+    ("0.00253583", Some(253_583)),
+    ("0.10000000", Some(10_000_000)),
+    ("", None),
+    (".", None),
+    ("-1", None),
+    ("0.000000001", None),
+    ("1,5", None),
+    ("1e3", Some(100_000_000_000)),
+    (" 1", None),
+];
+
+/// Satoshis, a denomination and how `display_in` formats them.
+pub static FORMAT_VECTORS: &[(u64, Denomination, &str)] = &[
+    (0, Denomination::Bitcoin, "0.00000000"),
+    (1, Denomination::Bitcoin, "0.00000001"),
+    (253_583, Denomination::Bitcoin, "0.00253583"),
+    (2_100_000_000_000_000, Denomination::Bitcoin, "21000000.00000000"),
+    (253_583, Denomination::MilliBitcoin, "2.53583"),
+    (253_583, Denomination::MicroBitcoin, "2535.83"),
+    (253_583, Denomination::Bit, "2535.83"),
+    (253_583, Denomination::Satoshi, "253583"),
+    (253_583, Denomination::MilliSatoshi, "253583000"),
+];
+
+/// A deterministic sequence of amounts within the valid money range, see
+/// [`amount_sequence`].
+#[derive(Debug, Clone)]
+pub struct AmountSequence {
+    state: u64,
+    edge_cases: usize,
+}
+
+/// Returns a sequence yielding the `EDGE_CASES` first and then an endless
+/// stream of pseudo-random amounts derived from `seed`, the same on every
+/// platform and release.
+pub fn amount_sequence(seed: u64) -> AmountSequence {
+    AmountSequence { state: seed, edge_cases: 0 }
+}
+
+impl Iterator for AmountSequence {
+    type Item = Amount;
+
+    fn next(&mut self) -> Option<Amount> {
+        if let Some(&amt) = EDGE_CASES.get(self.edge_cases) {
+            self.edge_cases += 1;
+            return Some(amt);
+        }
+
+        // SplitMix64, good enough and simple to keep stable.
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        Some(Amount(z % (Amount::MAX_MONEY.0 + 1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utils_vectors() {
+        for &(s, sat) in PARSE_VECTORS {
+            assert_eq!(s.parse::<Amount>().ok(), sat.map(Amount::from_sat), "{:?}", s);
+        }
+        for &(sat, denom, s) in FORMAT_VECTORS {
+            assert_eq!(Amount::from_sat(sat).display_in(denom).to_string(), s);
+            assert_eq!(Amount::from_str_in(s, denom), Ok(Amount::from_sat(sat)));
+        }
+    }
+
+    #[test]
+    fn test_utils_sequence() {
+        let amounts = amount_sequence(21).take(100).collect::<Vec<_>>();
+        assert_eq!(&amounts[..EDGE_CASES.len()], EDGE_CASES);
+        assert!(amounts.iter().all(|amt| amt.is_valid_money()));
+        assert_eq!(amounts, amount_sequence(21).take(100).collect::<Vec<_>>());
+        assert_ne!(amounts, amount_sequence(42).take(100).collect::<Vec<_>>());
+    }
+}